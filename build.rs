@@ -0,0 +1,95 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Writes one `example_test!` invocation per answer found next to the
+/// `examples/dayN/exampleK.txt` fixtures, included by `tests/examples.rs`.
+/// The answers of a fixture with a `skip: reason` line become ignored tests,
+/// a fixture without answers becomes a failing `example_without_answers!`.
+fn main() {
+    let root = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let examples = root.join("examples");
+    println!("cargo:rerun-if-changed={}", examples.display());
+
+    let mut tests = String::new();
+    for day in sorted_entries(&examples) {
+        let Some(day_name) = day.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let Some(number) = day_name
+            .strip_prefix("day")
+            .and_then(|d| d.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        for input in sorted_entries(&day) {
+            if input.extension().is_none_or(|e| e != "txt") {
+                continue;
+            }
+            let example = input.file_stem().unwrap().to_string_lossy().to_string();
+            let name = format!("{}_{}", day_name, identifier(&example));
+            let path = input.display().to_string();
+            let answers = fs::read_to_string(input.with_extension("answers")).unwrap_or_default();
+            let (answers, skipped) = parse_answers(&answers);
+            if answers.is_empty() {
+                tests.push_str(&format!(
+                    "example_without_answers!({}, {:?});\n",
+                    name, path
+                ));
+            }
+            let ignore = match skipped {
+                Some(reason) => format!(", ignore = {:?}", reason),
+                None => String::new(),
+            };
+            for (part, expected) in answers {
+                tests.push_str(&format!(
+                    "example_test!({}_part{}, {}, {}, {:?}, {:?}{});\n",
+                    name, part, number, part, path, expected, ignore
+                ));
+            }
+        }
+    }
+
+    let out = PathBuf::from(env::var("OUT_DIR").unwrap()).join("examples.rs");
+    fs::write(out, tests).unwrap();
+}
+
+fn sorted_entries(dir: &Path) -> Vec<PathBuf> {
+    let mut entries = fs::read_dir(dir)
+        .map(|entries| entries.filter_map(|e| e.ok().map(|e| e.path())).collect())
+        .unwrap_or_else(|_| Vec::new());
+    entries.sort();
+    entries
+}
+
+/// One `partN: answer` per line, blank lines and `#` comments are skipped.
+/// Reads the file like `answers::ExampleAnswers`, which the build script can
+/// not use, and returns the answers with the reason of a `skip:` line.
+fn parse_answers(answers: &str) -> (Vec<(u32, String)>, Option<String>) {
+    let mut parsed = Vec::new();
+    let mut skipped = None;
+    for line in answers.lines().map(str::trim) {
+        if line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim().to_string();
+        match key.trim() {
+            "skip" => skipped = Some(value),
+            key => {
+                if let Some(part) = key.strip_prefix("part").and_then(|p| p.parse().ok()) {
+                    parsed.push((part, value));
+                }
+            }
+        }
+    }
+    (parsed, skipped)
+}
+
+fn identifier(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
@@ -0,0 +1,170 @@
+//! Answers are integers, texts like the crates of day 5 or grids of pixels
+//! like the screen of day 10. In JSON integers are numbers and grids a list of
+//! rows.
+
+use std::fmt::{self, Display};
+
+use serde::{Serialize, Serializer};
+
+/// What a solver returns for a part, whatever type the puzzle asks for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Int(i128),
+    Text(String),
+    Grid(Grid),
+}
+
+/// Image drawn by a solver, like the letters on the screen of day 10. Lit
+/// pixels show as `#` and dark ones as `.`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid {
+    width: usize,
+    pixels: Vec<bool>,
+}
+
+impl Grid {
+    /// `pixels` go row by row, the last row can not be cut short.
+    pub fn new(width: usize, pixels: Vec<bool>) -> Self {
+        assert!(
+            width > 0 && pixels.len().is_multiple_of(width),
+            "{} pixels do not fill rows of {}",
+            pixels.len(),
+            width
+        );
+        Grid { width, pixels }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.pixels.len() / self.width
+    }
+
+    pub fn is_lit(&self, x: usize, y: usize) -> bool {
+        self.pixels[y * self.width + x]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = String> + '_ {
+        self.pixels
+            .chunks(self.width)
+            .map(|row| row.iter().map(|&lit| if lit { '#' } else { '.' }).collect())
+    }
+}
+
+impl Display for Grid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.rows().collect::<Vec<_>>().join("\n"))
+    }
+}
+
+/// A list of rows, so the image stays readable in JSON.
+impl Serialize for Grid {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.rows())
+    }
+}
+
+/// Integers that fit into 64 bits are numbers, larger ones are strings since
+/// not every serializer supports `i128`.
+impl Serialize for Answer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Answer::Int(n) => match (i64::try_from(*n), u64::try_from(*n)) {
+                (Ok(n), _) => serializer.serialize_i64(n),
+                (_, Ok(n)) => serializer.serialize_u64(n),
+                _ => serializer.serialize_str(&n.to_string()),
+            },
+            Answer::Text(text) => serializer.serialize_str(text),
+            Answer::Grid(grid) => grid.serialize(serializer),
+        }
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Int(n) => write!(f, "{}", n),
+            Answer::Text(text) => write!(f, "{}", text),
+            Answer::Grid(grid) => write!(f, "{}", grid),
+        }
+    }
+}
+
+macro_rules! int_answer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Answer::Int(n as i128)
+                }
+            }
+        )*
+    };
+}
+
+int_answer!(i32, i64, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        Answer::Text(text)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Self {
+        Answer::Text(text.to_string())
+    }
+}
+
+impl From<Grid> for Answer {
+    fn from(grid: Grid) -> Self {
+        Answer::Grid(grid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_should_display_answers() {
+        let grid = Grid::new(3, vec![true, false, true, false, true, false]);
+
+        assert_eq!(
+            Answer::from(11747175442119usize).to_string(),
+            "11747175442119"
+        );
+        assert_eq!(Answer::from("CMZ").to_string(), "CMZ");
+        assert_eq!(Answer::from(grid.clone()).to_string(), "#.#\n.#.");
+        assert_eq!(
+            (grid.width(), grid.height(), grid.is_lit(1, 1)),
+            (3, 2, true)
+        );
+    }
+
+    #[test]
+    fn it_should_serialise_answers_to_json() {
+        let grid = Grid::new(2, vec![true, false, false, true]);
+
+        assert_eq!(serde_json::to_value(Answer::from(-3)).unwrap(), json!(-3));
+        assert_eq!(
+            serde_json::to_value(Answer::from(u64::MAX)).unwrap(),
+            json!(u64::MAX)
+        );
+        assert_eq!(
+            serde_json::to_value(Answer::Int(i128::MIN)).unwrap(),
+            json!(i128::MIN.to_string())
+        );
+        assert_eq!(
+            serde_json::to_value(Answer::from("CMZ")).unwrap(),
+            json!("CMZ")
+        );
+        assert_eq!(
+            serde_json::to_value(Answer::from(grid)).unwrap(),
+            json!(["#.", ".#"])
+        );
+    }
+}
@@ -0,0 +1,195 @@
+//! `answers.toml` holds the accepted answers for the real inputs. `verify`
+//! records the answer of any part that has none, and skips the parts listed
+//! as `[[unsolved]]` since their solvers are known to be wrong.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum AnswersError {
+    Io(String),
+    InvalidToml(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedAnswer {
+    pub day: u32,
+    pub part: u32,
+    pub expected: String,
+}
+
+/// A part whose solver is known to give a wrong answer, so there is nothing
+/// to record for it yet.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnsolvedPart {
+    pub day: u32,
+    pub part: u32,
+    pub reason: String,
+}
+
+/// Accepted answers for the real inputs, stored as `[[answer]]` tables in
+/// `answers.toml`, and the parts known to be unsolved as `[[unsolved]]`.
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Answers {
+    #[serde(default, rename = "answer")]
+    answers: Vec<RecordedAnswer>,
+    #[serde(default, rename = "unsolved", skip_serializing_if = "Vec::is_empty")]
+    unsolved: Vec<UnsolvedPart>,
+}
+
+impl Answers {
+    pub fn parse(toml: &str) -> Result<Self, AnswersError> {
+        toml::from_str(toml).map_err(|e| AnswersError::InvalidToml(e.to_string()))
+    }
+
+    /// Loads the answers, a missing file has none recorded yet.
+    pub fn load(path: &Path) -> Result<Self, AnswersError> {
+        match fs::read_to_string(path) {
+            Ok(toml) => Answers::parse(&toml),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Answers::default()),
+            Err(e) => Err(AnswersError::Io(format!("{}: {}", path.display(), e))),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), AnswersError> {
+        fs::write(path, self.to_toml())
+            .map_err(|e| AnswersError::Io(format!("{}: {}", path.display(), e)))
+    }
+
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).unwrap()
+    }
+
+    pub fn expected(&self, day: u32, part: u32) -> Option<&str> {
+        self.answers
+            .iter()
+            .find(|a| a.day == day && a.part == part)
+            .map(|a| a.expected.as_str())
+    }
+
+    /// Why a part is known to be unsolved, `None` for the others.
+    pub fn unsolved(&self, day: u32, part: u32) -> Option<&str> {
+        self.unsolved
+            .iter()
+            .find(|u| u.day == day && u.part == part)
+            .map(|u| u.reason.as_str())
+    }
+
+    /// Records the answer of a part, replacing an earlier one. Answers stay
+    /// sorted by day and part so the file diffs nicely.
+    pub fn record(&mut self, day: u32, part: u32, expected: &str) {
+        let answer = RecordedAnswer {
+            day,
+            part,
+            expected: expected.to_string(),
+        };
+        match self
+            .answers
+            .binary_search_by_key(&(day, part), |a| (a.day, a.part))
+        {
+            Ok(i) => self.answers[i] = answer,
+            Err(i) => self.answers.insert(i, answer),
+        }
+    }
+}
+
+/// Expected answers of a puzzle example, read from the `exampleK.answers`
+/// file next to it: one `partN: answer` per line, `#` starts a comment.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ExampleAnswers {
+    pub answers: Vec<(u32, String)>,
+    /// Why the example is not checked, given by a `skip: reason` line for
+    /// solvers known to get it wrong.
+    pub skipped: Option<String>,
+}
+
+impl ExampleAnswers {
+    pub fn parse(text: &str) -> Self {
+        let mut answers = ExampleAnswers::default();
+        for line in text.lines().map(str::trim) {
+            if line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim().to_string();
+            match key.trim() {
+                "skip" => answers.skipped = Some(value),
+                key => {
+                    if let Some(part) = key.strip_prefix("part").and_then(|p| p.parse().ok()) {
+                        answers.answers.push((part, value));
+                    }
+                }
+            }
+        }
+        answers
+    }
+
+    /// A missing file has no answers.
+    pub fn load(path: &Path) -> Result<Self, AnswersError> {
+        match fs::read_to_string(path) {
+            Ok(text) => Ok(ExampleAnswers::parse(&text)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(ExampleAnswers::default()),
+            Err(e) => Err(AnswersError::Io(format!("{}: {}", path.display(), e))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_record_answers_in_order() {
+        let mut answers = Answers::default();
+        answers.record(2, 1, "15");
+        answers.record(1, 2, "45000");
+        answers.record(1, 1, "24000");
+        answers.record(2, 1, "16");
+
+        assert_eq!(answers.expected(1, 1), Some("24000"));
+        assert_eq!(answers.expected(2, 1), Some("16"));
+        assert_eq!(answers.expected(2, 2), None);
+
+        let toml = answers.to_toml();
+        assert!(toml.starts_with("[[answer]]\nday = 1\npart = 1\nexpected = \"24000\"\n"));
+        assert_eq!(Answers::parse(&toml), Ok(answers));
+    }
+
+    #[test]
+    fn it_should_reject_invalid_toml() {
+        assert!(matches!(
+            Answers::parse("[[answer]]\nday = \"one\""),
+            Err(AnswersError::InvalidToml(_))
+        ));
+        assert_eq!(Answers::parse(""), Ok(Answers::default()));
+    }
+
+    #[test]
+    fn it_should_keep_unsolved_parts() {
+        let toml = "[[answer]]\nday = 7\npart = 2\nexpected = \"214171\"\n\n\
+                    [[unsolved]]\nday = 7\npart = 1\nreason = \"stub\"\n";
+        let answers = Answers::parse(toml).unwrap();
+
+        assert_eq!(answers.unsolved(7, 1), Some("stub"));
+        assert_eq!(answers.unsolved(7, 2), None);
+        assert_eq!(Answers::parse(&answers.to_toml()), Ok(answers));
+    }
+
+    #[test]
+    fn it_should_parse_example_answers() {
+        let answers = ExampleAnswers::parse("# day 1\npart1: 24000\n\npart2: 45000\n");
+        assert_eq!(
+            answers.answers,
+            vec![(1, "24000".to_string()), (2, "45000".to_string())]
+        );
+        assert_eq!(answers.skipped, None);
+
+        let skipped = ExampleAnswers::parse("skip: wrong on the example\npart1: 95437");
+        assert_eq!(skipped.skipped, Some("wrong on the example".to_string()));
+    }
+}
@@ -0,0 +1,740 @@
+//! Runs the solvers without aoc-runner. `aoc run --day 9 --part 2` solves a
+//! part on `input/2022/day9.txt`, `--input path` or `--input -` reads another
+//! file or stdin, `--name alice` or `--all-inputs` runs the other inputs of the
+//! store and `run --all` solves every day. `test-examples` checks the solvers
+//! against `examples/`, `verify` against `answers.toml`, `inputs` lists the
+//! stored inputs and `list` the days. `--format json` prints machine-readable
+//! output.
+//!
+//! `--trace day11` (or `--trace all`) prints the `tracing` diagnostics of the
+//! solvers to stderr. Slow solvers get a progress bar, and with `--timeout
+//! SECONDS` a part that runs longer is reported as timed out.
+
+use std::fs;
+use std::io::{self, Read};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use adventofcode22::answer::Answer;
+use adventofcode22::answers::{Answers, ExampleAnswers};
+use adventofcode22::context::{Interrupted, Progress, SolveContext};
+use adventofcode22::inputs::{checksum, InputError, InputStore, LocalFiles, DEFAULT_INPUT};
+use adventofcode22::registry;
+use adventofcode22::solution::DynSolution;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+use serde_json::{json, Value};
+use tracing_subscriber::filter::filter_fn;
+use tracing_subscriber::prelude::*;
+
+#[derive(Parser)]
+#[command(name = "aoc", about = "Runs the Advent of Code 2022 solutions")]
+struct Cli {
+    #[arg(long, value_enum, default_value_t = Format::Text, global = true)]
+    format: Format,
+    /// Prints the diagnostics of a day (`day11`), or of every day with `all`,
+    /// to stderr
+    #[arg(long, global = true, value_name = "DAY", value_parser = trace_target)]
+    trace: Vec<String>,
+    /// Gives up on a part once it ran for this long
+    #[arg(long, global = true, value_name = "SECONDS", value_parser = parse_timeout)]
+    timeout: Option<Duration>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Text,
+    Json,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Runs the solvers of one day, or of every day with --all
+    Run {
+        #[arg(long, required_unless_present = "all")]
+        day: Option<u32>,
+        /// Only runs this part, both parts by default
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..=2))]
+        part: Option<u32>,
+        /// Input file, `-` reads from stdin. Defaults to `<INPUT_DIR>/dayN.txt`
+        #[arg(long, conflicts_with_all = ["all", "name", "all_inputs"])]
+        input: Option<String>,
+        /// Runs on a named input of the store instead of the default one
+        #[arg(long, conflicts_with = "all_inputs")]
+        name: Option<String>,
+        /// Runs on every input stored for the day
+        #[arg(long)]
+        all_inputs: bool,
+        #[arg(long, conflicts_with = "day")]
+        all: bool,
+        #[command(flatten)]
+        store: StoreArgs,
+    },
+    /// Checks the solvers against `<DIR>/dayN/exampleK.txt` and the answers
+    /// in `exampleK.answers`
+    TestExamples {
+        #[arg(long, default_value = "examples")]
+        dir: PathBuf,
+    },
+    /// Runs every solver on its input and compares the answers with the ones
+    /// recorded in the answers file. Answers that are missing get recorded,
+    /// parts listed as unsolved are skipped
+    Verify {
+        /// Only verifies this day
+        #[arg(long)]
+        day: Option<u32>,
+        #[arg(long, default_value = "answers.toml")]
+        answers: PathBuf,
+        #[command(flatten)]
+        store: StoreArgs,
+    },
+    /// Lists the stored inputs and whether they match their checksums
+    Inputs {
+        #[arg(long)]
+        day: Option<u32>,
+        /// Records the checksum of inputs that have none yet
+        #[arg(long)]
+        record: bool,
+        #[command(flatten)]
+        store: StoreArgs,
+    },
+    /// Lists the days and parts that can be run
+    List,
+}
+
+#[derive(Args)]
+struct StoreArgs {
+    /// Directory of the input store, with `dayN.txt` and `dayN/<NAME>.txt`
+    #[arg(long, default_value = "input/2022")]
+    input_dir: PathBuf,
+    /// Copies inputs missing from the store from this directory
+    #[arg(long, value_name = "DIR")]
+    fetch_from: Option<PathBuf>,
+}
+
+impl StoreArgs {
+    fn open(self) -> Result<InputStore, String> {
+        let store = InputStore::open(self.input_dir).map_err(input_error)?;
+        Ok(match self.fetch_from {
+            Some(dir) => store.with_fetch(Box::new(LocalFiles { dir })),
+            None => store,
+        })
+    }
+}
+
+struct Outcome {
+    day: u32,
+    part: u32,
+    /// Name of the example, only set when checking examples.
+    example: Option<String>,
+    /// Name of the stored input, only set when not running on the default.
+    input: Option<String>,
+    answer: Result<Answer, String>,
+    expected: Option<String>,
+    /// Whether the answer was recorded as the expected one by this run.
+    recorded: bool,
+    elapsed: Duration,
+}
+
+impl Outcome {
+    fn passed(&self) -> bool {
+        match (&self.answer, &self.expected) {
+            (Ok(answer), Some(expected)) => answer.to_string() == *expected,
+            (Ok(_), None) => true,
+            (Err(_), _) => false,
+        }
+    }
+
+    fn status(&self) -> &'static str {
+        match (&self.answer, &self.expected) {
+            (Err(_), _) => "error",
+            _ if self.recorded => "new",
+            (Ok(answer), Some(expected)) if answer.to_string() != *expected => "FAIL",
+            (Ok(_), Some(_)) => "pass",
+            (Ok(_), None) => "",
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        let mut value = json!({
+            "day": self.day,
+            "part": self.part,
+            "time_ms": self.elapsed.as_secs_f64() * 1000.0,
+        });
+        match &self.answer {
+            Ok(answer) => value["answer"] = json!(answer),
+            Err(error) => value["error"] = json!(error),
+        }
+        if let Some(example) = &self.example {
+            value["example"] = json!(example);
+        }
+        if let Some(input) = &self.input {
+            value["input"] = json!(input);
+        }
+        if let Some(expected) = &self.expected {
+            value["expected"] = json!(expected);
+            value["passed"] = json!(self.passed());
+        }
+        if self.expected.is_some() || self.recorded {
+            value["status"] = json!(self.status());
+        }
+        value
+    }
+
+    fn to_text(&self) -> String {
+        let name = match self.example.as_ref().or(self.input.as_ref()) {
+            Some(input) => format!("day {:>2} {} part {}", self.day, input, self.part),
+            None => format!("day {:>2} part {}", self.day, self.part),
+        };
+        match (&self.answer, &self.expected) {
+            (Err(error), _) => format!("{}: error: {}", name, error),
+            (Ok(answer), Some(expected)) if answer.to_string() != *expected => {
+                format!(
+                    "{}: FAILED, expected {} but got {}",
+                    name,
+                    expected,
+                    shown(answer)
+                )
+            }
+            (Ok(answer), Some(_)) => format!("{}: ok {}", name, shown(answer)),
+            (Ok(answer @ Answer::Grid(_)), None) => {
+                format!("{} ({:.2?}):{}", name, self.elapsed, shown(answer))
+            }
+            (Ok(answer), None) => format!("{}: {} ({:.2?})", name, answer, self.elapsed),
+        }
+    }
+}
+
+/// Images start on their own line so their rows line up.
+fn shown(answer: &Answer) -> String {
+    match answer {
+        Answer::Grid(_) => format!("\n{}", answer),
+        _ => answer.to_string(),
+    }
+}
+
+fn parse_timeout(seconds: &str) -> Result<Duration, String> {
+    seconds
+        .parse::<f64>()
+        .ok()
+        .and_then(|s| Duration::try_from_secs_f64(s).ok())
+        .ok_or_else(|| format!("expected a number of seconds, got `{}`", seconds))
+}
+
+fn trace_target(day: &str) -> Result<String, String> {
+    if day == "all" {
+        return Ok("adventofcode22".to_string());
+    }
+    match day.strip_prefix("day").and_then(|d| d.parse().ok()) {
+        Some(number) if registry::solution(number).is_some() => {
+            Ok(format!("adventofcode22::{}", day))
+        }
+        _ => Err(format!(
+            "expected `all` or a solved day like `day11`, got `{}`",
+            day
+        )),
+    }
+}
+
+/// Diagnostics are off unless asked for. Modules are matched exactly, so
+/// tracing `day1` leaves out `day10` to `day15`, the spans of the solver
+/// registry are always kept for context.
+fn init_tracing(targets: Vec<String>) {
+    if targets.is_empty() {
+        return;
+    }
+    let filter = filter_fn(move |metadata| {
+        let target = metadata.target();
+        target == "adventofcode22::solution"
+            || targets.iter().any(|t| {
+                target == t
+                    || target
+                        .strip_prefix(t.as_str())
+                        .is_some_and(|r| r.starts_with("::"))
+            })
+    });
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::fmt::layer().with_writer(io::stderr))
+        .with(filter)
+        .init();
+}
+
+/// Reads an input as it is, the solvers normalise it themselves.
+fn read_input(path: &str) -> Result<String, String> {
+    let mut input = String::new();
+    if path == "-" {
+        io::stdin()
+            .read_to_string(&mut input)
+            .map_err(|e| format!("cannot read stdin: {}", e))?;
+    } else {
+        input = fs::read_to_string(path).map_err(|e| format!("cannot read {}: {}", path, e))?;
+    }
+    Ok(input)
+}
+
+/// How long a solver gets past its budget to notice it ran out of time.
+const GRACE: Duration = Duration::from_millis(100);
+
+/// Shows the progress of solvers that report it on stderr, and nothing for
+/// the others.
+struct Bar(ProgressBar);
+
+impl Progress for Bar {
+    fn start(&self, total: u64) {
+        self.0.set_length(total);
+        self.0.set_draw_target(ProgressDrawTarget::stderr());
+    }
+
+    fn advance(&self, steps: u64) {
+        self.0.inc(steps);
+    }
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "solver panicked".to_string())
+}
+
+/// Solves a part on its own thread, turning a panic into an error so one
+/// broken day does not stop the others. Once over budget the solver is asked
+/// to stop, one that never checks its context is left running in the
+/// background.
+fn solve(
+    solution: &'static dyn DynSolution,
+    part: u32,
+    input: &str,
+    budget: Option<Duration>,
+) -> (Result<Answer, String>, Duration) {
+    let bar = ProgressBar::hidden()
+        .with_style(ProgressStyle::with_template("{prefix} [{bar:40}] {pos}/{len} {eta}").unwrap())
+        .with_prefix(format!("day {} part {}", solution.day(), part));
+    let mut context = SolveContext::new().with_progress(Arc::new(Bar(bar.clone())));
+    if let Some(budget) = budget {
+        context = context.with_budget(budget);
+    }
+    let cancellation = context.cancellation();
+    let (sender, receiver) = mpsc::channel();
+    let input = input.to_string();
+
+    let start = Instant::now();
+    thread::spawn(move || {
+        let answer = panic::catch_unwind(AssertUnwindSafe(|| {
+            solution.solve_with(part, &input, &context).unwrap()
+        }));
+        let _ = sender.send(answer);
+    });
+    let answer = match budget {
+        Some(budget) => receiver.recv_timeout(budget + GRACE),
+        None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+    };
+    let elapsed = start.elapsed();
+    bar.finish_and_clear();
+
+    let answer = match answer {
+        Ok(Ok(Ok(answer))) => Ok(answer),
+        Ok(Ok(Err(Interrupted::TimedOut))) | Err(RecvTimeoutError::Timeout) => {
+            cancellation.cancel();
+            Err(format!("timed out after {:.2?}", budget.unwrap()))
+        }
+        Ok(Ok(Err(Interrupted::Cancelled))) => Err("cancelled".to_string()),
+        Ok(Err(payload)) => Err(panic_message(payload)),
+        Err(RecvTimeoutError::Disconnected) => Err("solver stopped without an answer".to_string()),
+    };
+    (answer, elapsed)
+}
+
+fn run_day(
+    solution: &'static dyn DynSolution,
+    parts: &[u32],
+    input: Result<String, String>,
+    budget: Option<Duration>,
+) -> Vec<Outcome> {
+    parts
+        .iter()
+        .filter(|part| solution.parts().contains(part))
+        .map(|&part| {
+            let (answer, elapsed) = match &input {
+                Ok(input) => solve(solution, part, input, budget),
+                Err(error) => (Err(error.clone()), Duration::ZERO),
+            };
+            Outcome {
+                day: solution.day(),
+                part,
+                example: None,
+                input: None,
+                answer,
+                expected: None,
+                recorded: false,
+                elapsed,
+            }
+        })
+        .collect()
+}
+
+fn test_examples(dir: &Path, budget: Option<Duration>) -> Vec<Outcome> {
+    let mut outcomes = Vec::new();
+    for &solution in registry::solutions() {
+        let Ok(entries) = fs::read_dir(dir.join(format!("day{}", solution.day()))) else {
+            continue;
+        };
+        let mut examples = entries
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.extension().is_some_and(|e| e == "txt"))
+            .collect::<Vec<_>>();
+        examples.sort();
+        for path in examples {
+            let input = read_input(&path.to_string_lossy());
+            let name = path.file_stem().unwrap().to_string_lossy().to_string();
+            let answers = match ExampleAnswers::load(&path.with_extension("answers")) {
+                Ok(ExampleAnswers {
+                    skipped: Some(reason),
+                    ..
+                }) => {
+                    eprintln!("skipping day {} {}: {}", solution.day(), name, reason);
+                    continue;
+                }
+                Ok(answers) => answers.answers,
+                Err(e) => {
+                    eprintln!("cannot load answers: {:?}", e);
+                    continue;
+                }
+            };
+            for (part, expected) in answers {
+                let mut outcome = run_day(solution, &[part], input.clone(), budget);
+                if outcome.is_empty() {
+                    continue;
+                }
+                let mut outcome = outcome.remove(0);
+                outcome.example = Some(name.clone());
+                outcome.expected = Some(expected);
+                outcomes.push(outcome);
+            }
+        }
+    }
+    outcomes
+}
+
+fn input_error(error: InputError) -> String {
+    match error {
+        InputError::Missing(day, name) => format!("no input `{}` stored for day {}", name, day),
+        InputError::ChecksumMismatch(day, name) => {
+            format!(
+                "input `{}` of day {} does not match its checksum",
+                name, day
+            )
+        }
+        InputError::Io(error) | InputError::InvalidToml(error) => error,
+    }
+}
+
+/// Runs a day on stored inputs, every one of them or the one with `name`.
+fn run_stored(
+    solution: &'static dyn DynSolution,
+    parts: &[u32],
+    store: &mut InputStore,
+    name: Option<&str>,
+    budget: Option<Duration>,
+) -> Vec<Outcome> {
+    let day = solution.day();
+    let names = match name {
+        Some(name) => vec![name.to_string()],
+        None => match store.names(day) {
+            Ok(names) if !names.is_empty() => names,
+            Ok(_) => {
+                let error = format!("no inputs stored for day {}", day);
+                return run_day(solution, parts, Err(error), budget);
+            }
+            Err(error) => return run_day(solution, parts, Err(input_error(error)), budget),
+        },
+    };
+    names
+        .into_iter()
+        .flat_map(|name| {
+            let input = store.load(day, &name).map_err(input_error);
+            let mut outcomes = run_day(solution, parts, input, budget);
+            if name != DEFAULT_INPUT {
+                outcomes
+                    .iter_mut()
+                    .for_each(|o| o.input = Some(name.clone()));
+            }
+            outcomes
+        })
+        .collect()
+}
+
+fn verify(
+    solutions: &[&'static dyn DynSolution],
+    store: &mut InputStore,
+    answers: &mut Answers,
+    budget: Option<Duration>,
+) -> Vec<Outcome> {
+    let mut outcomes = Vec::new();
+    for &solution in solutions {
+        let input = store
+            .load(solution.day(), DEFAULT_INPUT)
+            .map_err(input_error);
+        let parts = [1, 2]
+            .into_iter()
+            .filter(|&part| match answers.unsolved(solution.day(), part) {
+                Some(reason) => {
+                    eprintln!(
+                        "skipping day {} part {}, known unsolved: {}",
+                        solution.day(),
+                        part,
+                        reason
+                    );
+                    false
+                }
+                None => true,
+            })
+            .collect::<Vec<_>>();
+        for mut outcome in run_day(solution, &parts, input, budget) {
+            match (answers.expected(outcome.day, outcome.part), &outcome.answer) {
+                (Some(expected), _) => outcome.expected = Some(expected.to_string()),
+                (None, Ok(answer)) => {
+                    answers.record(outcome.day, outcome.part, &answer.to_string());
+                    outcome.recorded = true;
+                }
+                (None, Err(_)) => {}
+            }
+            outcomes.push(outcome);
+        }
+    }
+    outcomes
+}
+
+/// Checks every stored input against its checksum, recording the missing
+/// ones when asked to. Fails if an input changed or cannot be read.
+fn list_inputs(store: &mut InputStore, days: &[u32], record: bool, format: Format) -> bool {
+    let mut rows = Vec::new();
+    for &day in days {
+        let names = match store.names(day) {
+            Ok(names) => names,
+            Err(e) => {
+                rows.push((day, String::new(), "error", input_error(e)));
+                continue;
+            }
+        };
+        for name in names {
+            let path = store.path(day, &name);
+            let sha256 = match fs::read_to_string(&path) {
+                Ok(input) => checksum(&input),
+                Err(e) => {
+                    rows.push((day, name, "error", format!("{}: {}", path.display(), e)));
+                    continue;
+                }
+            };
+            let status = match store.checksum(day, &name) {
+                Some(recorded) if recorded == sha256 => "ok",
+                Some(_) => "CHANGED",
+                None if record => match store.record(day, &name, &sha256) {
+                    Ok(()) => "new",
+                    Err(e) => {
+                        rows.push((day, name, "error", input_error(e)));
+                        continue;
+                    }
+                },
+                None => "",
+            };
+            rows.push((day, name, status, sha256));
+        }
+    }
+    match format {
+        Format::Text => {
+            println!("day  {:<10}  status   sha256", "input");
+            for (day, name, status, sha256) in &rows {
+                println!("{:>3}  {:<10}  {:<7}  {}", day, name, status, sha256);
+            }
+        }
+        Format::Json => {
+            let values = rows
+                .iter()
+                .map(|(day, name, status, detail)| match *status {
+                    "error" => json!({ "day": day, "input": name, "error": detail }),
+                    _ => json!({ "day": day, "input": name, "status": status, "sha256": detail }),
+                })
+                .collect::<Vec<_>>();
+            println!("{}", Value::Array(values));
+        }
+    }
+    rows.iter()
+        .all(|(_, _, status, _)| !matches!(*status, "CHANGED" | "error"))
+}
+
+fn print_table(outcomes: &[Outcome]) {
+    println!("day  part  status  {:>10}  answer", "time");
+    for o in outcomes {
+        let answer = match (&o.answer, &o.expected) {
+            (Err(error), _) => error.clone(),
+            (Ok(answer), Some(expected)) if answer.to_string() != *expected => {
+                format!("{} (expected {})", shown(answer), expected)
+            }
+            (Ok(answer), _) => shown(answer),
+        };
+        println!(
+            "{:>3}  {:>4}  {:<6}  {:>10}  {}",
+            o.day,
+            o.part,
+            o.status(),
+            format!("{:.2?}", o.elapsed),
+            answer
+        );
+    }
+}
+
+fn print(outcomes: &[Outcome], format: Format) {
+    match format {
+        Format::Text => outcomes.iter().for_each(|o| println!("{}", o.to_text())),
+        Format::Json => {
+            let values = outcomes.iter().map(Outcome::to_json).collect::<Vec<_>>();
+            println!("{}", Value::Array(values));
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    init_tracing(cli.trace);
+    let outcomes = match cli.command {
+        Command::Run {
+            day,
+            part,
+            input,
+            name,
+            all_inputs,
+            all,
+            store,
+        } => {
+            let parts = part.map_or(vec![1, 2], |p| vec![p]);
+            let days = if all {
+                registry::solutions().to_vec()
+            } else {
+                let day = day.unwrap();
+                match registry::solution(day) {
+                    Some(d) => vec![d],
+                    None => {
+                        eprintln!("day {} is not solved", day);
+                        return ExitCode::FAILURE;
+                    }
+                }
+            };
+            if let Some(path) = input {
+                return finish(
+                    run_day(days[0], &parts, read_input(&path), cli.timeout),
+                    cli.format,
+                );
+            }
+            let mut store = match store.open() {
+                Ok(store) => store,
+                Err(e) => {
+                    eprintln!("cannot open the input store: {}", e);
+                    return ExitCode::FAILURE;
+                }
+            };
+            let name = match (all_inputs, &name) {
+                (true, _) => None,
+                (false, name) => Some(name.as_deref().unwrap_or(DEFAULT_INPUT)),
+            };
+            days.into_iter()
+                .flat_map(|s| run_stored(s, &parts, &mut store, name, cli.timeout))
+                .collect::<Vec<_>>()
+        }
+        Command::TestExamples { dir } => test_examples(&dir, cli.timeout),
+        Command::Verify {
+            day,
+            answers: answers_path,
+            store,
+        } => {
+            let solutions = registry::solutions()
+                .iter()
+                .copied()
+                .filter(|s| day.is_none_or(|d| s.day() == d))
+                .collect::<Vec<_>>();
+            let mut answers = match Answers::load(&answers_path) {
+                Ok(answers) => answers,
+                Err(e) => {
+                    eprintln!("cannot load answers: {:?}", e);
+                    return ExitCode::FAILURE;
+                }
+            };
+            let mut store = match store.open() {
+                Ok(store) => store,
+                Err(e) => {
+                    eprintln!("cannot open the input store: {}", e);
+                    return ExitCode::FAILURE;
+                }
+            };
+            let outcomes = verify(&solutions, &mut store, &mut answers, cli.timeout);
+            if outcomes.iter().any(|o| o.recorded) {
+                if let Err(e) = answers.save(&answers_path) {
+                    eprintln!("cannot save answers: {:?}", e);
+                    return ExitCode::FAILURE;
+                }
+            }
+            match cli.format {
+                Format::Text => print_table(&outcomes),
+                Format::Json => print(&outcomes, cli.format),
+            }
+            return if outcomes.iter().all(Outcome::passed) {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            };
+        }
+        Command::Inputs { day, record, store } => {
+            let days = registry::solutions()
+                .iter()
+                .map(|s| s.day())
+                .filter(|&d| day.is_none_or(|day| d == day))
+                .collect::<Vec<_>>();
+            let passed = match store.open() {
+                Ok(mut store) => list_inputs(&mut store, &days, record, cli.format),
+                Err(e) => {
+                    eprintln!("cannot open the input store: {}", e);
+                    false
+                }
+            };
+            return if passed {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            };
+        }
+        Command::List => {
+            match cli.format {
+                Format::Text => registry::solutions().iter().for_each(|s| {
+                    let parts = s.parts().iter().map(u32::to_string).collect::<Vec<_>>();
+                    println!("day {:>2}: part {}", s.day(), parts.join(", "));
+                }),
+                Format::Json => {
+                    let days = registry::solutions()
+                        .iter()
+                        .map(|s| json!({ "day": s.day(), "parts": s.parts() }))
+                        .collect::<Vec<_>>();
+                    println!("{}", Value::Array(days));
+                }
+            }
+            return ExitCode::SUCCESS;
+        }
+    };
+    finish(outcomes, cli.format)
+}
+
+fn finish(outcomes: Vec<Outcome>, format: Format) -> ExitCode {
+    print(&outcomes, format);
+    if outcomes.iter().all(Outcome::passed) {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
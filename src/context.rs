@@ -0,0 +1,139 @@
+//! Slow solvers take a [`SolveContext`] that reports their progress and stops
+//! them when cancelled or out of time.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Why a solver stopped before finding the answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interrupted {
+    Cancelled,
+    TimedOut,
+}
+
+/// Shared flag to ask a running solver to stop.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Receives the progress of a long running solver.
+pub trait Progress: Send + Sync {
+    /// Called once the solver knows how many steps it will take.
+    fn start(&self, total: u64);
+    fn advance(&self, steps: u64);
+}
+
+/// Passed to solvers that run long enough to want progress reporting and a
+/// way to stop early. Solvers call [`SolveContext::check`] between steps and
+/// return the error when it fails.
+#[derive(Clone, Default)]
+pub struct SolveContext {
+    cancellation: CancellationToken,
+    deadline: Option<Instant>,
+    progress: Option<Arc<dyn Progress>>,
+}
+
+impl SolveContext {
+    pub fn new() -> Self {
+        SolveContext::default()
+    }
+
+    /// Times out once `budget` has passed from now.
+    pub fn with_budget(mut self, budget: Duration) -> Self {
+        self.deadline = Some(Instant::now() + budget);
+        self
+    }
+
+    pub fn with_progress(mut self, progress: Arc<dyn Progress>) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    pub fn with_cancellation(mut self, cancellation: CancellationToken) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    pub fn cancellation(&self) -> CancellationToken {
+        self.cancellation.clone()
+    }
+
+    pub fn start(&self, total: u64) {
+        if let Some(progress) = &self.progress {
+            progress.start(total);
+        }
+    }
+
+    pub fn advance(&self, steps: u64) {
+        if let Some(progress) = &self.progress {
+            progress.advance(steps);
+        }
+    }
+
+    pub fn check(&self) -> Result<(), Interrupted> {
+        if self.cancellation.is_cancelled() {
+            Err(Interrupted::Cancelled)
+        } else if self.deadline.is_some_and(|d| Instant::now() >= d) {
+            Err(Interrupted::TimedOut)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicU64;
+
+    #[derive(Default)]
+    struct Counter {
+        total: AtomicU64,
+        done: AtomicU64,
+    }
+
+    impl Progress for Counter {
+        fn start(&self, total: u64) {
+            self.total.store(total, Ordering::Relaxed);
+        }
+
+        fn advance(&self, steps: u64) {
+            self.done.fetch_add(steps, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn it_should_report_progress() {
+        let counter = Arc::new(Counter::default());
+        let context = SolveContext::new().with_progress(counter.clone());
+        context.start(10);
+        context.advance(3);
+        context.advance(4);
+
+        assert_eq!(counter.total.load(Ordering::Relaxed), 10);
+        assert_eq!(counter.done.load(Ordering::Relaxed), 7);
+    }
+
+    #[test]
+    fn it_should_stop_when_cancelled_or_out_of_time() {
+        let context = SolveContext::new();
+        assert_eq!(context.check(), Ok(()));
+        context.cancellation().cancel();
+        assert_eq!(context.check(), Err(Interrupted::Cancelled));
+
+        let context = SolveContext::new().with_budget(Duration::ZERO);
+        assert_eq!(context.check(), Err(Interrupted::TimedOut));
+        let context = SolveContext::new().with_budget(Duration::from_secs(60));
+        assert_eq!(context.check(), Ok(()));
+    }
+}
@@ -1,36 +1,292 @@
-#[derive(Debug)]
+use crate::solution::impl_solution;
+use std::borrow::Borrow;
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    InvalidCalories(usize, String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Elf {
-    score: u32,
+    /// Position of the elf in the input, starting at 0.
+    id: usize,
+    items: Vec<u32>,
+}
+
+impl Elf {
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    pub fn items(&self) -> &[u32] {
+        &self.items
+    }
+
+    /// Sum of the items, as `u64` so that many large items can not overflow it.
+    pub fn calories(&self) -> u64 {
+        self.items.iter().map(|&c| u64::from(c)).sum()
+    }
+}
+
+/// Parses elves lazily from lines of input, one elf per block of lines.
+/// Several blank lines in a row count as a single separator.
+pub struct Elves<I> {
+    lines: I,
+    line_number: usize,
+    next_id: usize,
+}
+
+impl<I, S> Iterator for Elves<I>
+where
+    I: Iterator<Item = S>,
+    S: AsRef<str>,
+{
+    type Item = Result<Elf, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut items = Vec::new();
+        for line in self.lines.by_ref() {
+            self.line_number += 1;
+            let line = line.as_ref().trim();
+            if line.is_empty() {
+                if items.is_empty() {
+                    continue;
+                }
+                break;
+            }
+            match line.parse::<u32>() {
+                Ok(calories) => items.push(calories),
+                Err(_) => {
+                    return Some(Err(ParseError::InvalidCalories(
+                        self.line_number,
+                        line.to_string(),
+                    )))
+                }
+            }
+        }
+        if items.is_empty() {
+            return None;
+        }
+        self.next_id += 1;
+        Some(Ok(Elf {
+            id: self.next_id - 1,
+            items,
+        }))
+    }
+}
+
+pub fn elves<I, S>(lines: I) -> Elves<I::IntoIter>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    Elves {
+        lines: lines.into_iter(),
+        line_number: 0,
+        next_id: 0,
+    }
 }
 
 #[aoc_generator(day1)]
 pub fn input_generator(input: &str) -> Vec<Elf> {
-    let lines = input.lines().collect::<Vec<&str>>();
-    let calories_per_elf = lines.split(|l| l.is_empty());
-    let a = calories_per_elf.map(|v| Elf {
-        score: v.iter().map(|s| s.parse::<u32>().unwrap()).sum(),
-    });
-    return a.collect();
+    elves(input.lines()).collect::<Result<_, _>>().unwrap()
 }
 
 #[aoc(day1, part1)]
-pub fn solve_part1(input: &[Elf]) -> u32 {
-    match elfes_sorted(input).first() {
-        Some(c) => c.score,
-        None => 0,
-    }
+pub fn solve_part1(input: &[Elf]) -> u64 {
+    top_k(input, 1).iter().map(|e| e.calories()).sum()
 }
 
 #[aoc(day1, part2)]
-pub fn solve_part2(input: &[Elf]) -> u32 {
-    let elfes = elfes_sorted(input);
-    let (top3, _) = elfes.split_at(3);
-    top3.iter().map(|e| e.score).sum()
+pub fn solve_part2(input: &[Elf]) -> u64 {
+    top_k(input, 3).iter().map(|e| e.calories()).sum()
 }
 
-fn elfes_sorted(elfes: &[Elf]) -> Vec<&Elf> {
-    let mut result = elfes.iter().collect::<Vec<&Elf>>();
-    result.sort_by(|e1, e2| e1.score.cmp(&e2.score));
-    result.reverse();
-    result
+/// Orders elves by calories, on a tie the elf that comes first ranks higher.
+struct Ranked<E>(u64, Reverse<usize>, E);
+
+impl<E> PartialEq for Ranked<E> {
+    fn eq(&self, other: &Self) -> bool {
+        (self.0, self.1) == (other.0, other.1)
+    }
+}
+
+impl<E> Eq for Ranked<E> {}
+
+impl<E> PartialOrd for Ranked<E> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<E> Ord for Ranked<E> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.0, self.1).cmp(&(other.0, other.1))
+    }
+}
+
+/// The `k` elves carrying the most calories, most calories first. Only keeps
+/// `k` elves in memory, so it works on a stream of elves as well.
+pub fn top_k<E, I>(elves: I, k: usize) -> Vec<E>
+where
+    E: Borrow<Elf>,
+    I: IntoIterator<Item = E>,
+{
+    let mut heap = BinaryHeap::with_capacity(k + 1);
+    for e in elves {
+        let elf = e.borrow();
+        heap.push(Reverse(Ranked(elf.calories(), Reverse(elf.id), e)));
+        if heap.len() > k {
+            heap.pop();
+        }
+    }
+    heap.into_sorted_vec()
+        .into_iter()
+        .map(|Reverse(Ranked(_, _, e))| e)
+        .collect()
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Summary {
+    pub elves: usize,
+    pub total_calories: u64,
+    pub mean: f64,
+    pub median: f64,
+    /// Id of the elf carrying the most items, the first one on a tie.
+    pub most_items: Option<usize>,
+}
+
+pub fn summary(elves: &[Elf]) -> Summary {
+    let mut calories = elves.iter().map(|e| e.calories()).collect::<Vec<_>>();
+    calories.sort();
+    let total_calories = calories.iter().sum();
+    let median = match calories.len() {
+        0 => 0.0,
+        n if n % 2 == 1 => calories[n / 2] as f64,
+        n => (calories[n / 2 - 1] as f64 + calories[n / 2] as f64) / 2.0,
+    };
+    Summary {
+        elves: elves.len(),
+        total_calories,
+        mean: if elves.is_empty() {
+            0.0
+        } else {
+            total_calories as f64 / elves.len() as f64
+        },
+        median,
+        most_items: elves
+            .iter()
+            .max_by_key(|e| (e.items.len(), Reverse(e.id)))
+            .map(|e| e.id),
+    }
+}
+
+pub struct Day1;
+
+impl_solution! {
+    Day1 {
+        day: 1,
+        input: Vec<Elf>,
+        parse: input_generator,
+        part1: solve_part1,
+        part2: solve_part2,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE_INPUT: &str = "1000
+2000
+3000
+
+4000
+
+5000
+6000
+
+7000
+8000
+9000
+
+10000";
+
+    #[test]
+    fn it_should_parse_elves() {
+        let input = input_generator(EXAMPLE_INPUT);
+
+        assert_eq!(input.len(), 5);
+        assert_eq!(input[0].id(), 0);
+        assert_eq!(input[0].items(), &[1000, 2000, 3000]);
+        assert_eq!(input[4].id(), 4);
+        assert_eq!(input[4].calories(), 10000);
+    }
+
+    #[test]
+    fn it_should_solve_example() {
+        let input = input_generator(EXAMPLE_INPUT);
+
+        assert_eq!(solve_part1(&input), 24000);
+        assert_eq!(solve_part2(&input), 45000);
+    }
+
+    #[test]
+    fn it_should_handle_short_input() {
+        assert_eq!(solve_part1(&input_generator("")), 0);
+        assert_eq!(solve_part2(&input_generator("")), 0);
+        assert_eq!(solve_part2(&input_generator("1\n2\n\n\n\n3")), 6);
+    }
+
+    #[test]
+    fn it_should_stream_top_k() {
+        let lines = EXAMPLE_INPUT.lines().map(String::from);
+        let top = top_k(elves(lines).map(|e| e.unwrap()), 2);
+
+        assert_eq!(top.iter().map(|e| e.id()).collect::<Vec<_>>(), vec![3, 2]);
+        assert!(top_k(elves(EXAMPLE_INPUT.lines()).map(|e| e.unwrap()), 0).is_empty());
+
+        let ties = input_generator("5\n\n5\n\n5");
+        let top = top_k(&ties, 2);
+        assert_eq!(top.iter().map(|e| e.id()).collect::<Vec<_>>(), vec![0, 1]);
+    }
+
+    #[test]
+    fn it_should_report_invalid_calories() {
+        let result = elves("1\n\n2x".lines()).collect::<Result<Vec<_>, _>>();
+
+        assert_eq!(
+            result,
+            Err(ParseError::InvalidCalories(3, "2x".to_string()))
+        );
+    }
+
+    #[test]
+    fn it_should_summarise() {
+        let result = summary(&input_generator(EXAMPLE_INPUT));
+
+        assert_eq!(
+            result,
+            Summary {
+                elves: 5,
+                total_calories: 55000,
+                mean: 11000.0,
+                median: 10000.0,
+                most_items: Some(0),
+            }
+        );
+        assert_eq!(summary(&[]).most_items, None);
+        assert_eq!(summary(&input_generator("1\n\n3")).median, 2.0);
+    }
+
+    #[test]
+    fn it_should_sum_calories_beyond_u32() {
+        let input = input_generator("4294967295\n1\n\n4294967295");
+
+        assert_eq!(input[0].calories(), 4294967296);
+        assert_eq!(solve_part2(&input), 8589934591);
+        assert_eq!(summary(&input).total_calories, 8589934591);
+    }
 }
@@ -1,3 +1,6 @@
+use crate::answer::Grid;
+use crate::solution::impl_solution;
+
 pub enum Operation {
     ADDX(i32),
     NOOP,
@@ -25,7 +28,7 @@ pub fn solve_part1(input: &[Operation]) -> i32 {
     let cycle_values: Vec<i32> = cycle_values(input);
 
     let during_20 = cycle_values[18] * 20;
-    println!("during 20 {:?}", during_20);
+    tracing::debug!(during_20);
     let during_60 = cycle_values[58] * 60;
     let during_100 = cycle_values[98] * 100;
     let during_140 = cycle_values[138] * 140;
@@ -50,27 +53,40 @@ fn cycle_values(input: &[Operation]) -> Vec<i32> {
     cycle_values
 }
 #[aoc(day10, part2)]
-pub fn solve_part2(input: &[Operation]) -> i32 {
+pub fn solve_part2(input: &[Operation]) -> Grid {
     let cycle_values: Vec<i32> = cycle_values(input);
     let mut row = 0;
+    let mut pixels = Vec::new();
     cycle_values.iter().enumerate().for_each(|(i, v)| {
         let pos = i - (row * 40);
         let sprite_range_start = if pos == 0 { 0 } else { *v - 1 };
         let sprite_range = sprite_range_start..=(sprite_range_start + 2);
 
-        //println!("value {:?} , i {:?}, pos {:?}, sprit {:?}", v, i, pos, sprite_range);
+        tracing::trace!(v, i, pos, ?sprite_range);
 
-        if sprite_range.contains(&(pos as i32)) {
-            print!("#")
-        } else {
-            print!(".")
-        };
+        pixels.push(sprite_range.contains(&(pos as i32)));
         if (i + 1) % 40 == 0 {
-            print!("\n");
             row += 1;
         }
     });
-    1
+    let screen = Grid::new(40, pixels);
+    screen
+        .rows()
+        .enumerate()
+        .for_each(|(row, line)| tracing::info!(row, "{}", line));
+    screen
+}
+
+pub struct Day10;
+
+impl_solution! {
+    Day10 {
+        day: 10,
+        input: Vec<Operation>,
+        parse: input_generator,
+        part1: solve_part1,
+        part2: solve_part2,
+    }
 }
 
 #[cfg(test)]
@@ -79,154 +95,7 @@ mod tests {
 
     #[test]
     fn it_should_solve_part1() {
-        let input = input_generator(
-            "addx 15
-        addx -11
-        addx 6
-        addx -3
-        addx 5
-        addx -1
-        addx -8
-        addx 13
-        addx 4
-        noop
-        addx -1
-        addx 5
-        addx -1
-        addx 5
-        addx -1
-        addx 5
-        addx -1
-        addx 5
-        addx -1
-        addx -35
-        addx 1
-        addx 24
-        addx -19
-        addx 1
-        addx 16
-        addx -11
-        noop
-        noop
-        addx 21
-        addx -15
-        noop
-        noop
-        addx -3
-        addx 9
-        addx 1
-        addx -3
-        addx 8
-        addx 1
-        addx 5
-        noop
-        noop
-        noop
-        noop
-        noop
-        addx -36
-        noop
-        addx 1
-        addx 7
-        noop
-        noop
-        noop
-        addx 2
-        addx 6
-        noop
-        noop
-        noop
-        noop
-        noop
-        addx 1
-        noop
-        noop
-        addx 7
-        addx 1
-        noop
-        addx -13
-        addx 13
-        addx 7
-        noop
-        addx 1
-        addx -33
-        noop
-        noop
-        noop
-        addx 2
-        noop
-        noop
-        noop
-        addx 8
-        noop
-        addx -1
-        addx 2
-        addx 1
-        noop
-        addx 17
-        addx -9
-        addx 1
-        addx 1
-        addx -3
-        addx 11
-        noop
-        noop
-        addx 1
-        noop
-        addx 1
-        noop
-        noop
-        addx -13
-        addx -19
-        addx 1
-        addx 3
-        addx 26
-        addx -30
-        addx 12
-        addx -1
-        addx 3
-        addx 1
-        noop
-        noop
-        noop
-        addx -9
-        addx 18
-        addx 1
-        addx 2
-        noop
-        noop
-        addx 9
-        noop
-        noop
-        noop
-        addx -1
-        addx 2
-        addx -37
-        addx 1
-        addx 3
-        noop
-        addx 15
-        addx -21
-        addx 22
-        addx -6
-        addx 1
-        noop
-        addx 2
-        addx 1
-        noop
-        addx -10
-        noop
-        noop
-        addx 20
-        addx 1
-        addx 2
-        addx 2
-        addx -6
-        addx -11
-        noop
-        noop
-        noop",
-        );
+        let input = input_generator(include_str!("../examples/day10/example1.txt"));
         let result = solve_part1(&input);
 
         assert_eq!(result, 13140);
@@ -234,156 +103,17 @@ mod tests {
 
     #[test]
     fn it_should_solve_part2() {
-        let input = input_generator(
-            "addx 15
-                addx -11
-                addx 6
-                addx -3
-                addx 5
-                addx -1
-                addx -8
-                addx 13
-                addx 4
-                noop
-                addx -1
-                addx 5
-                addx -1
-                addx 5
-                addx -1
-                addx 5
-                addx -1
-                addx 5
-                addx -1
-                addx -35
-                addx 1
-                addx 24
-                addx -19
-                addx 1
-                addx 16
-                addx -11
-                noop
-                noop
-                addx 21
-                addx -15
-                noop
-                noop
-                addx -3
-                addx 9
-                addx 1
-                addx -3
-                addx 8
-                addx 1
-                addx 5
-                noop
-                noop
-                noop
-                noop
-                noop
-                addx -36
-                noop
-                addx 1
-                addx 7
-                noop
-                noop
-                noop
-                addx 2
-                addx 6
-                noop
-                noop
-                noop
-                noop
-                noop
-                addx 1
-                noop
-                noop
-                addx 7
-                addx 1
-                noop
-                addx -13
-                addx 13
-                addx 7
-                noop
-                addx 1
-                addx -33
-                noop
-                noop
-                noop
-                addx 2
-                noop
-                noop
-                noop
-                addx 8
-                noop
-                addx -1
-                addx 2
-                addx 1
-                noop
-                addx 17
-                addx -9
-                addx 1
-                addx 1
-                addx -3
-                addx 11
-                noop
-                noop
-                addx 1
-                noop
-                addx 1
-                noop
-                noop
-                addx -13
-                addx -19
-                addx 1
-                addx 3
-                addx 26
-                addx -30
-                addx 12
-                addx -1
-                addx 3
-                addx 1
-                noop
-                noop
-                noop
-                addx -9
-                addx 18
-                addx 1
-                addx 2
-                noop
-                noop
-                addx 9
-                noop
-                noop
-                noop
-                addx -1
-                addx 2
-                addx -37
-                addx 1
-                addx 3
-                noop
-                addx 15
-                addx -21
-                addx 22
-                addx -6
-                addx 1
-                noop
-                addx 2
-                addx 1
-                noop
-                addx -10
-                noop
-                noop
-                addx 20
-                addx 1
-                addx 2
-                addx 2
-                addx -6
-                addx -11
-                noop
-                noop
-                noop",
-        );
+        let input = input_generator(include_str!("../examples/day10/example1.txt"));
         let result = solve_part2(&input);
 
-        assert_eq!(result, 13140);
+        assert_eq!(
+            result.to_string(),
+            "##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######....."
+        );
     }
 }
@@ -1,3 +1,5 @@
+use crate::context::{Interrupted, SolveContext};
+use crate::solution::impl_solution;
 use std::collections::HashMap;
 
 use sscanf::sscanf;
@@ -107,7 +109,7 @@ pub fn solve_part1(input: &[Monkey]) -> usize {
     });
     let mut monkey_count: HashMap<usize, usize> = HashMap::new();
     for round in 1..=20 {
-        dbg!(round);
+        tracing::trace!(round);
         for monkey in monkeys {
             let monkey_items_copy = monkey_items.clone();
             let items = monkey_items_copy.get(&monkey.id).unwrap();
@@ -139,6 +141,12 @@ pub fn solve_part1(input: &[Monkey]) -> usize {
 
 #[aoc(day11, part2)]
 pub fn solve_part2(input: &[Monkey]) -> usize {
+    solve_part2_with(input, &SolveContext::new()).unwrap()
+}
+
+/// Reports one step of progress per round and stops between rounds when
+/// interrupted.
+pub fn solve_part2_with(input: &[Monkey], context: &SolveContext) -> Result<usize, Interrupted> {
     let monkeys = input.clone();
     let mut monkey_items: HashMap<usize, Vec<usize>> = HashMap::new();
     monkeys.iter().for_each(|m| {
@@ -150,8 +158,10 @@ pub fn solve_part2(input: &[Monkey]) -> usize {
         .iter()
         .map(|m| m.test_divisible_by)
         .product::<usize>();
+    context.start(10000);
     for round in 1..=10000 {
-        dbg!(round);
+        context.check()?;
+        tracing::trace!(round);
         for monkey in monkeys {
             let monkey_items_copy = monkey_items.clone();
             let items = monkey_items_copy.get(&monkey.id).unwrap();
@@ -176,11 +186,26 @@ pub fn solve_part2(input: &[Monkey]) -> usize {
                     .or_insert(1);
             }
         }
+        context.advance(1);
     }
     let mut counts = monkey_count.iter().collect::<Vec<_>>();
     counts.sort_by(|a, b| b.1.cmp(a.1));
-    counts.iter().take(2).map(|v| v.1).fold(1, |a, b| a * b)
+    Ok(counts.iter().take(2).map(|v| v.1).fold(1, |a, b| a * b))
+}
+
+pub struct Day11;
+
+impl_solution! {
+    Day11 {
+        day: 11,
+        input: Vec<Monkey>,
+        parse: input_generator,
+        part1: solve_part1,
+        part2: solve_part2,
+        part2_with: solve_part2_with,
+    }
 }
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -230,4 +255,16 @@ mod tests {
 
         assert_eq!(result, 2713310158)
     }
+
+    #[test]
+    fn it_should_stop_part2_when_cancelled() {
+        let input = input_generator(EXAMPLE_INPUT);
+        let context = SolveContext::new();
+        context.cancellation().cancel();
+
+        assert_eq!(
+            solve_part2_with(&input, &context),
+            Err(Interrupted::Cancelled)
+        );
+    }
 }
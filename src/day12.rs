@@ -1,11 +1,13 @@
-use pathfinding::prelude::dijkstra;
+use crate::solution::impl_solution;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
 
 #[derive(Debug)]
 pub struct Grid {
     start: Pos,
     destination: Pos,
-    width: u8,
-    height: u8,
+    width: usize,
+    height: usize,
     rows: Vec<Vec<u8>>,
 }
 
@@ -15,14 +17,18 @@ impl Grid {
     }
 
     fn is_in(&self, p: &Pos) -> bool {
-        p.x >= 0 && p.y >= 0 && p.x < self.width as i16 && p.y < self.height as i16
+        p.x >= 0 && p.y >= 0 && (p.x as usize) < self.width && (p.y as usize) < self.height
+    }
+
+    fn positions(&self) -> impl Iterator<Item = Pos> + '_ {
+        (0..self.height as i32).flat_map(|y| (0..self.width as i32).map(move |x| Pos { x, y }))
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
 pub struct Pos {
-    x: i16,
-    y: i16,
+    x: i32,
+    y: i32,
 }
 
 impl Pos {
@@ -53,7 +59,6 @@ impl Pos {
 }
 #[aoc_generator(day12)]
 pub fn input_generator(input: &str) -> Grid {
-    let alphabet = ('a'..='z').collect::<Vec<_>>();
     let mut start = Pos { x: 0, y: 0 };
     let mut destination = Pos { x: 0, y: 0 };
     let rows = input
@@ -62,27 +67,23 @@ pub fn input_generator(input: &str) -> Grid {
         .enumerate()
         .map(|(ri, v)| {
             v.char_indices()
-                .map(|(ci, c)| {
-                    if c == 'S' {
+                .map(|(ci, c)| match c {
+                    'S' => {
                         start = Pos {
-                            x: ci as i16,
-                            y: ri as i16,
+                            x: ci as i32,
+                            y: ri as i32,
                         };
                         0
-                    } else if c == 'E' {
+                    }
+                    'E' => {
                         destination = Pos {
-                            x: ci as i16,
-                            y: ri as i16,
+                            x: ci as i32,
+                            y: ri as i32,
                         };
                         25
-                    } else {
-                        alphabet
-                            .iter()
-                            .enumerate()
-                            .find(|(_, v)| **v == c)
-                            .unwrap()
-                            .0 as u8
                     }
+                    'a'..='z' => c as u8 - b'a',
+                    _ => panic!("invalid elevation {}", c),
                 })
                 .collect::<Vec<_>>()
         })
@@ -90,70 +91,351 @@ pub fn input_generator(input: &str) -> Grid {
     Grid {
         start,
         destination,
-        width: rows.first().unwrap().len() as u8,
-        height: rows.len() as u8,
+        width: rows.first().unwrap().len(),
+        height: rows.len(),
         rows,
     }
 }
 
-pub fn possible_moves(pos: &Pos, elevation: &u8, grid: &Grid) -> Vec<(Pos, usize)> {
-    vec![pos.up(), pos.down(), pos.left(), pos.right()]
-        .iter()
-        .filter(|o| grid.is_in(o))
-        .filter(|p| grid.at(p) as i16 - *elevation as i16 <= 1)
-        .map(|p| (*p, 1))
-        .collect::<Vec<_>>()
+#[derive(Debug, PartialEq, Eq)]
+pub enum ClimbError {
+    /// A step with this change in elevation costs nothing, so a route could
+    /// walk back and forth for free.
+    FreeStep(i16),
+}
+
+/// Which moves are allowed between two neighbouring cells and what they cost.
+#[derive(Debug, Clone, Copy)]
+pub struct ClimbRules {
+    /// How much higher the next cell may be.
+    pub max_ascent: i16,
+    /// How much lower the next cell may be, `None` allows any descent.
+    pub max_descent: Option<i16>,
+    /// Whether the four diagonal neighbours can be reached in one step.
+    pub diagonal: bool,
+    /// Cost of a step, given the elevation of the next cell minus the current one.
+    /// Every allowed step has to cost at least 1.
+    pub step_cost: fn(i16) -> usize,
+}
+
+impl Default for ClimbRules {
+    /// The rules of the puzzle: up at most one, down any, no diagonals, every step costs 1.
+    fn default() -> Self {
+        ClimbRules {
+            max_ascent: 1,
+            max_descent: None,
+            diagonal: false,
+            step_cost: |_| 1,
+        }
+    }
+}
+
+impl ClimbRules {
+    fn allows(&self, from: u8, to: u8) -> bool {
+        self.allows_step(to as i16 - from as i16)
+    }
+
+    fn allows_step(&self, delta: i16) -> bool {
+        delta <= self.max_ascent && self.max_descent.is_none_or(|d| -delta <= d)
+    }
+
+    fn cost(&self, from: u8, to: u8) -> usize {
+        (self.step_cost)(to as i16 - from as i16)
+    }
+
+    /// Elevations go from `a` to `z`, so these are all the steps there can be.
+    fn check(&self) -> Result<(), ClimbError> {
+        match (-25..=25)
+            .filter(|delta| self.allows_step(*delta))
+            .find(|delta| (self.step_cost)(*delta) == 0)
+        {
+            Some(delta) => Err(ClimbError::FreeStep(delta)),
+            None => Ok(()),
+        }
+    }
+
+    fn neighbours(&self, pos: &Pos, grid: &Grid) -> Vec<Pos> {
+        let mut neighbours = vec![pos.up(), pos.down(), pos.left(), pos.right()];
+        if self.diagonal {
+            neighbours.extend([
+                pos.up().left(),
+                pos.up().right(),
+                pos.down().left(),
+                pos.down().right(),
+            ]);
+        }
+        neighbours.retain(|p| grid.is_in(p));
+        neighbours
+    }
+
+    /// Cells reachable in one step from `pos`, with the cost of the step.
+    pub fn moves(&self, pos: &Pos, grid: &Grid) -> Vec<(Pos, usize)> {
+        let elevation = grid.at(pos);
+        self.neighbours(pos, grid)
+            .into_iter()
+            .filter(|p| self.allows(elevation, grid.at(p)))
+            .map(|p| (p, self.cost(elevation, grid.at(&p))))
+            .collect()
+    }
+
+    /// Cells from which `pos` can be reached in one step, with the cost of the step.
+    pub fn reverse_moves(&self, pos: &Pos, grid: &Grid) -> Vec<(Pos, usize)> {
+        let elevation = grid.at(pos);
+        self.neighbours(pos, grid)
+            .into_iter()
+            .filter(|p| self.allows(grid.at(p), elevation))
+            .map(|p| (p, self.cost(grid.at(&p), elevation)))
+            .collect()
+    }
+}
+
+pub fn possible_moves(pos: &Pos, grid: &Grid) -> Vec<(Pos, usize)> {
+    ClimbRules::default().moves(pos, grid)
+}
+
+/// Cost of reaching the nearest target from every cell of the grid.
+/// Costs are kept as `u32` so maps with hundreds of millions of cells fit into memory.
+pub struct DistanceMap<'a> {
+    grid: &'a Grid,
+    rules: ClimbRules,
+    distances: Vec<u32>,
+}
+
+const UNREACHABLE: u32 = u32::MAX;
+#[cfg(feature = "parallel")]
+const MIN_PARALLEL_LEVEL: usize = 1024;
+
+impl<'a> DistanceMap<'a> {
+    /// Searches backwards from the destination, so a single pass answers
+    /// how far the destination is from every cell.
+    #[cfg(not(feature = "parallel"))]
+    pub fn to_destination(grid: &'a Grid) -> Self {
+        DistanceMap::search(grid, &[grid.destination], &ClimbRules::default())
+    }
+
+    /// Every step costs 1 under the default rules, so the search goes level
+    /// by level and the cells of a level are expanded on all cores. The first
+    /// to reach a cell claims it, and every claim in a level writes the same
+    /// distance, so the map is the same as sequentially.
+    #[cfg(feature = "parallel")]
+    pub fn to_destination(grid: &'a Grid) -> Self {
+        use rayon::prelude::*;
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        let rules = ClimbRules::default();
+        let index = |p: &Pos| p.y as usize * grid.width + p.x as usize;
+        let distances = (0..grid.width * grid.height)
+            .map(|_| AtomicU32::new(UNREACHABLE))
+            .collect::<Vec<_>>();
+        distances[index(&grid.destination)].store(0, Ordering::Relaxed);
+        let mut level = vec![grid.destination];
+        let mut distance = 0;
+        while !level.is_empty() {
+            distance += 1;
+            let claim = |(p, _): &(Pos, usize)| {
+                distances[index(p)]
+                    .compare_exchange(UNREACHABLE, distance, Ordering::Relaxed, Ordering::Relaxed)
+                    .is_ok()
+            };
+            // splitting small levels costs more than expanding them
+            level = if level.len() < MIN_PARALLEL_LEVEL {
+                level
+                    .iter()
+                    .flat_map(|pos| rules.reverse_moves(pos, grid))
+                    .filter(claim)
+                    .map(|(p, _)| p)
+                    .collect()
+            } else {
+                level
+                    .par_iter()
+                    .flat_map_iter(|pos| rules.reverse_moves(pos, grid))
+                    .filter(claim)
+                    .map(|(p, _)| p)
+                    .collect()
+            };
+        }
+        DistanceMap {
+            grid,
+            rules,
+            distances: distances.into_iter().map(AtomicU32::into_inner).collect(),
+        }
+    }
+
+    /// Dijkstra from all targets at once, following the moves in reverse.
+    /// With the default rules every step costs 1 and this is a breadth first search.
+    /// Cells whose cost does not fit below `u32::MAX` count as unreachable.
+    pub fn to_targets(
+        grid: &'a Grid,
+        targets: &[Pos],
+        rules: &ClimbRules,
+    ) -> Result<Self, ClimbError> {
+        rules.check()?;
+        Ok(DistanceMap::search(grid, targets, rules))
+    }
+
+    /// [`DistanceMap::to_targets`] for rules known to be valid.
+    fn search(grid: &'a Grid, targets: &[Pos], rules: &ClimbRules) -> Self {
+        let index = |p: &Pos| p.y as usize * grid.width + p.x as usize;
+        let mut distances = vec![UNREACHABLE; grid.width * grid.height];
+        let mut queue = BinaryHeap::new();
+        for t in targets {
+            distances[index(t)] = 0;
+            queue.push(Reverse((0, *t)));
+        }
+        while let Some(Reverse((distance, pos))) = queue.pop() {
+            if distances[index(&pos)] < distance {
+                continue;
+            }
+            for (p, cost) in rules.reverse_moves(&pos, grid) {
+                let next = u32::try_from(cost)
+                    .ok()
+                    .and_then(|cost| distance.checked_add(cost))
+                    .unwrap_or(UNREACHABLE);
+                if next < distances[index(&p)] {
+                    distances[index(&p)] = next;
+                    queue.push(Reverse((next, p)));
+                }
+            }
+        }
+        DistanceMap {
+            grid,
+            rules: *rules,
+            distances,
+        }
+    }
+
+    pub fn distance(&self, pos: &Pos) -> Option<usize> {
+        if !self.grid.is_in(pos) {
+            return None;
+        }
+        match self.distances[pos.y as usize * self.grid.width + pos.x as usize] {
+            UNREACHABLE => None,
+            d => Some(d as usize),
+        }
+    }
+
+    /// The cell with the given elevation that is closest to the targets.
+    pub fn nearest_with_elevation(&self, elevation: u8) -> Option<(Pos, usize)> {
+        self.grid
+            .positions()
+            .filter(|p| self.grid.at(p) == elevation)
+            .filter_map(|p| self.distance(&p).map(|d| (p, d)))
+            .min_by_key(|(_, d)| *d)
+    }
+
+    /// Cheapest route from `from` to the nearest target, found by always
+    /// stepping to a neighbour whose distance accounts for the cost of the step.
+    pub fn route_from(&self, from: &Pos) -> Option<Route> {
+        let cost = self.distance(from)?;
+        let mut path = vec![*from];
+        let mut remaining = cost;
+        while remaining > 0 {
+            let (next, step_cost) = self
+                .rules
+                .moves(path.last().unwrap(), self.grid)
+                .into_iter()
+                .find(|(p, c)| {
+                    remaining
+                        .checked_sub(*c)
+                        .is_some_and(|left| self.distance(p) == Some(left))
+                })?;
+            remaining -= step_cost;
+            path.push(next);
+        }
+        Some(Route { path, cost })
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Route {
+    pub path: Vec<Pos>,
+    pub cost: usize,
+}
+
+impl Route {
+    pub fn steps(&self) -> usize {
+        self.path.len() - 1
+    }
+
+    /// Draws the route over the grid like the puzzle does, with an arrow on
+    /// every cell pointing to the next one and `E` at the end. Diagonal
+    /// steps are drawn as `/` and `\`.
+    pub fn render(&self, grid: &Grid) -> String {
+        let mut cells = vec![vec!['.'; grid.width]; grid.height];
+        for step in self.path.windows(2) {
+            let (from, to) = (step[0], step[1]);
+            cells[from.y as usize][from.x as usize] = match (to.x - from.x, to.y - from.y) {
+                (1, 0) => '>',
+                (-1, 0) => '<',
+                (0, -1) => '^',
+                (0, 1) => 'v',
+                (1, -1) | (-1, 1) => '/',
+                _ => '\\',
+            };
+        }
+        if let Some(end) = self.path.last() {
+            cells[end.y as usize][end.x as usize] = 'E';
+        }
+        cells
+            .iter()
+            .map(|r| r.iter().collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// `Ok(None)` if `to` can not be reached from `from`.
+pub fn find_route(
+    grid: &Grid,
+    from: &Pos,
+    to: &Pos,
+    rules: &ClimbRules,
+) -> Result<Option<Route>, ClimbError> {
+    Ok(DistanceMap::to_targets(grid, &[*to], rules)?.route_from(from))
+}
+
+/// Fewest steps from the start to the destination, `None` if it can not be reached.
+pub fn fewest_steps(grid: &Grid) -> Option<usize> {
+    DistanceMap::search(grid, &[grid.destination], &ClimbRules::default())
+        .route_from(&grid.start)
+        .map(|r| r.steps())
+}
+
+/// Fewest steps to the destination from any cell at elevation `a`, `None` if
+/// none of them can reach it.
+pub fn fewest_steps_from_lowest(grid: &Grid) -> Option<usize> {
+    DistanceMap::to_destination(grid)
+        .nearest_with_elevation(0)
+        .map(|(_, steps)| steps)
 }
 
 #[aoc(day12, part1)]
 pub fn solve_part1(grid: &Grid) -> usize {
-    let result = dijkstra(
-        &grid.start,
-        |p| possible_moves(p, &grid.at(&p), grid),
-        |p| *p == grid.destination,
-    );
-    result.unwrap().0.len() - 1
+    fewest_steps(grid).expect("no route to the destination")
 }
 
 #[aoc(day12, part2)]
 pub fn solve_part2(grid: &Grid) -> usize {
-    let mut starting_points: Vec<Pos> = Vec::new();
-    for (ir, row) in grid.rows.iter().enumerate() {
-        for (ic, col) in row.iter().enumerate() {
-            if *col == 0 {
-                starting_points.push(Pos {
-                    x: ic as i16,
-                    y: ir as i16,
-                })
-            }
-        }
+    fewest_steps_from_lowest(grid).expect("no route from elevation a to the destination")
+}
+
+pub struct Day12;
+
+impl_solution! {
+    Day12 {
+        day: 12,
+        input: Grid,
+        parse: input_generator,
+        part1: solve_part1,
+        part2: solve_part2,
     }
-    let mut results = starting_points
-        .into_iter()
-        .map(|s| {
-            dijkstra(
-                &s,
-                |p| possible_moves(p, &grid.at(&p), grid),
-                |p| *p == grid.destination,
-            )
-        })
-        .filter(|r| r.is_some())
-        .map(|r| r.unwrap())
-        .map(|r| r.0.len() - 1)
-        .collect::<Vec<_>>();
-    results.sort();
-    *results.first().unwrap()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const EXAMPLE_INPUT: &str = "Sabqponm
-    abcryxxl
-    accszExk
-    acctuvwj
-    abdefghi";
+    const EXAMPLE_INPUT: &str = include_str!("../examples/day12/example1.txt");
 
     #[test]
     fn it_should_generate_input() {
@@ -172,7 +454,7 @@ mod tests {
     fn it_should_get_possible_moves() {
         let input = input_generator(EXAMPLE_INPUT);
 
-        let result = possible_moves(&input.start, &input.at(&input.start), &input);
+        let result = possible_moves(&input.start, &input);
         assert_eq!(result.len(), 2);
         assert_eq!(result.contains(&(Pos { x: 1, y: 0 }, 1)), true);
         assert_eq!(result.contains(&(Pos { x: 0, y: 1 }, 1)), true);
@@ -183,7 +465,7 @@ mod tests {
         let input = input_generator(EXAMPLE_INPUT);
 
         let pos = Pos { x: 3, y: 1 };
-        let result = possible_moves(&pos, &input.at(&pos), &input);
+        let result = possible_moves(&pos, &input);
         assert_eq!(result.len(), 3);
         assert_eq!(result.contains(&(pos.up(), 1)), true);
         assert_eq!(result.contains(&(pos.down(), 1)), true);
@@ -198,4 +480,259 @@ mod tests {
         let result = solve_part1(&input);
         assert_eq!(result, 31);
     }
+
+    #[test]
+    fn it_should_solve_part2() {
+        let input = input_generator(EXAMPLE_INPUT);
+
+        let result = solve_part2(&input);
+        assert_eq!(result, 29);
+    }
+
+    #[test]
+    fn it_should_compute_distance_map() {
+        let input = input_generator(EXAMPLE_INPUT);
+        let distances = DistanceMap::to_destination(&input);
+
+        assert_eq!(distances.distance(&input.destination), Some(0));
+        assert_eq!(distances.distance(&input.start), Some(31));
+        assert_eq!(distances.distance(&Pos { x: 0, y: 4 }), Some(29));
+        assert_eq!(distances.distance(&Pos { x: -1, y: 0 }), None);
+        assert_eq!(
+            distances.nearest_with_elevation(0),
+            Some((Pos { x: 0, y: 4 }, 29))
+        );
+        assert_eq!(distances.nearest_with_elevation(24).map(|n| n.1), Some(2));
+    }
+
+    #[test]
+    fn it_should_not_reach_walled_in_cells() {
+        let input = input_generator("Saz\nzzE");
+        let distances = DistanceMap::to_destination(&input);
+
+        assert_eq!(distances.distance(&input.start), None);
+        assert_eq!(distances.nearest_with_elevation(0), None);
+        assert_eq!(fewest_steps(&input), None);
+        assert_eq!(fewest_steps_from_lowest(&input), None);
+    }
+
+    #[test]
+    fn it_should_find_route() {
+        let input = input_generator(EXAMPLE_INPUT);
+        let route = find_route(
+            &input,
+            &input.start,
+            &input.destination,
+            &ClimbRules::default(),
+        )
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(route.steps(), 31);
+        assert_eq!(route.cost, 31);
+        assert_eq!(route.path.first(), Some(&input.start));
+        assert_eq!(route.path.last(), Some(&input.destination));
+        let rendered = route.render(&input);
+        assert_eq!(rendered.lines().count(), 5);
+        assert_eq!(rendered.chars().filter(|c| "<>^v".contains(*c)).count(), 31);
+        assert_eq!(rendered.lines().nth(2).unwrap().chars().nth(5), Some('E'));
+    }
+
+    #[test]
+    fn it_should_render_route() {
+        let input = input_generator("Sbc\nfed\nghE");
+        let route = find_route(
+            &input,
+            &input.start,
+            &input.destination,
+            &ClimbRules {
+                max_descent: Some(0),
+                ..ClimbRules::default()
+            },
+        );
+        assert_eq!(route, Ok(None));
+
+        let route = find_route(
+            &input,
+            &input.start,
+            &input.destination,
+            &ClimbRules {
+                max_ascent: 25,
+                ..ClimbRules::default()
+            },
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(route.steps(), 4);
+
+        let route = find_route(
+            &input,
+            &input.start,
+            &input.destination,
+            &ClimbRules {
+                max_ascent: 25,
+                diagonal: true,
+                ..ClimbRules::default()
+            },
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(route.steps(), 2);
+        assert_eq!(route.render(&input), "\\..\n.\\.\n..E");
+    }
+
+    #[test]
+    fn it_should_use_step_costs() {
+        let input = input_generator("SbcdE");
+        let rules = ClimbRules {
+            max_ascent: 25,
+            step_cost: |delta| 1 + delta.unsigned_abs() as usize * 10,
+            ..ClimbRules::default()
+        };
+        let route = find_route(&input, &input.start, &input.destination, &rules)
+            .unwrap()
+            .unwrap();
+        assert_eq!(route.steps(), 4);
+        assert_eq!(route.cost, 11 + 11 + 11 + 221);
+
+        let distances = DistanceMap::to_targets(&input, &[input.destination], &rules).unwrap();
+        assert_eq!(distances.distance(&input.start), Some(route.cost));
+    }
+
+    #[test]
+    fn it_should_route_past_neighbours_costing_more_than_what_is_left() {
+        let input = input_generator("SyzE");
+        let rules = ClimbRules {
+            max_ascent: 25,
+            step_cost: |delta| 1 + delta.unsigned_abs() as usize * 10,
+            ..ClimbRules::default()
+        };
+        let route = find_route(&input, &input.start, &input.destination, &rules)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(route.steps(), 3);
+        assert_eq!(route.cost, 241 + 11 + 1);
+    }
+
+    #[test]
+    fn it_should_reject_free_steps() {
+        let input = input_generator("SbcdefghijklmnopqrstuvwxyE");
+        let rules = ClimbRules {
+            max_ascent: 25,
+            step_cost: |delta| delta.unsigned_abs() as usize,
+            ..ClimbRules::default()
+        };
+        assert_eq!(
+            find_route(&input, &input.start, &input.destination, &rules),
+            Err(ClimbError::FreeStep(0))
+        );
+
+        // flat steps are free, but only climbing one at a time is allowed
+        let rules = ClimbRules {
+            max_ascent: 1,
+            max_descent: Some(-1),
+            ..rules
+        };
+        let route = find_route(&input, &input.start, &input.destination, &rules)
+            .unwrap()
+            .unwrap();
+        assert_eq!(route.steps(), 25);
+    }
+
+    #[test]
+    fn it_should_treat_costs_beyond_u32_as_unreachable() {
+        let input = input_generator("SyE");
+        let rules = ClimbRules {
+            max_ascent: 25,
+            step_cost: |_| u32::MAX as usize - 1,
+            ..ClimbRules::default()
+        };
+        let distances = DistanceMap::to_targets(&input, &[input.destination], &rules).unwrap();
+
+        assert_eq!(
+            distances.distance(&Pos { x: 1, y: 0 }),
+            Some(u32::MAX as usize - 1)
+        );
+        assert_eq!(distances.distance(&input.start), None);
+
+        let rules = ClimbRules {
+            step_cost: |_| usize::MAX,
+            ..rules
+        };
+        let distances = DistanceMap::to_targets(&input, &[input.destination], &rules).unwrap();
+        assert_eq!(distances.distance(&Pos { x: 1, y: 0 }), None);
+    }
+
+    /// Elevation grows by at most one per step from `S` in the top left
+    /// corner to `E` in the bottom right corner.
+    fn generated_map(width: usize, height: usize) -> String {
+        let diagonal = width + height - 2;
+        (0..height)
+            .map(|y| {
+                (0..width)
+                    .map(|x| match (x, y) {
+                        (0, 0) => 'S',
+                        _ if x == width - 1 && y == height - 1 => 'E',
+                        _ => (b'a' + ((x + y) * 25 / diagonal) as u8) as char,
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn assert_solves_generated_map(width: usize, height: usize) {
+        let input = input_generator(&generated_map(width, height));
+        assert_eq!(input.width, width);
+        assert_eq!(input.height, height);
+        assert_eq!(
+            input.destination,
+            Pos {
+                x: width as i32 - 1,
+                y: height as i32 - 1
+            }
+        );
+
+        let diagonal = width + height - 2;
+        assert_eq!(solve_part1(&input), diagonal);
+        // the lowest cells are those with (x + y) * 25 < diagonal
+        let furthest_a = (diagonal - 1) / 25;
+        assert_eq!(solve_part2(&input), diagonal - furthest_a);
+    }
+
+    #[test]
+    fn it_should_map_distances_like_a_search_from_the_destination() {
+        // a flat map with the destination in the middle, the levels of the
+        // search grow to over a thousand cells
+        let size = 601;
+        let mut map = vec![vec!['z'; size]; size];
+        map[0][0] = 'S';
+        map[size / 2][size / 2] = 'E';
+        let map = map
+            .iter()
+            .map(|row| row.iter().collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let input = input_generator(&map);
+        let rules = ClimbRules::default();
+
+        assert_eq!(
+            DistanceMap::to_destination(&input).distances,
+            DistanceMap::to_targets(&input, &[input.destination], &rules)
+                .unwrap()
+                .distances
+        );
+    }
+
+    #[test]
+    fn it_should_solve_large_maps() {
+        assert_solves_generated_map(1000, 300);
+    }
+
+    #[test]
+    #[ignore = "takes about a minute in a release build, run with --release -- --ignored"]
+    fn it_should_solve_huge_maps() {
+        assert_solves_generated_map(10_000, 10_000);
+    }
 }
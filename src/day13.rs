@@ -1,3 +1,4 @@
+use crate::solution::impl_solution;
 use eval::{eval};
 use std::cmp::Ordering;
 
@@ -48,7 +49,7 @@ fn to_items(line: &str) -> Vec<Item> {
             eval::Value::Array(i) => Item::List(
                 i.iter()
                     .map(|a| {
-                        println!("{:?}", a);
+                        tracing::trace!(?a, "list element");
                         a.as_u64().unwrap() as i32
                     })
                     .collect(),
@@ -79,34 +80,13 @@ pub fn solve_part1(input: &Vec<Vec<Item>>) -> usize {
     sum
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+pub struct Day13;
 
-    const EXAMPLE_INPUT: &str = "[1,1,3,1,1]
-    [1,1,5,1,1]
-
-    [[1],[2,3,4]]
-    [[1],4]
-
-    [9]
-    [[8,7,6]]
-
-    [[4,4],4,4]
-    [[4,4],4,4,4]
-
-    [7,7,7,7]
-    [7,7,7]
-
-    []
-    [3]
-
-    [[[]]]
-    [[]]
-
-    [1,[2,[3,[4,[5,6,7]]]],8,9]
-    [1,[2,[3,[4,[5,6,0]]]],8,9]";
-
-    #[test]
-    fn it_should_generate_input() {}
+impl_solution! {
+    Day13 {
+        day: 13,
+        input: Vec<Vec<Item>>,
+        parse: input_generator,
+        part1: solve_part1,
+    }
 }
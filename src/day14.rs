@@ -1,5 +1,5 @@
-use std::cmp::{max, min};
-use std::collections::{HashSet};
+use crate::solution::impl_solution;
+use std::collections::HashSet;
 #[derive(Hash, Clone, PartialEq, Eq, Debug)]
 pub struct Point {
     x: i32,
@@ -7,135 +7,338 @@ pub struct Point {
 }
 
 impl Point {
-    fn down(&self) -> Self {
-        Point {
-            x: self.x,
-            y: self.y + 1,
-        }
+    pub fn new(x: i32, y: i32) -> Self {
+        Point { x, y }
     }
 
-    fn diagonal_left(&self) -> Self {
+    fn moved(&self, (dx, dy): (i32, i32)) -> Self {
         Point {
-            x: self.x - 1,
-            y: self.y + 1,
+            x: self.x + dx,
+            y: self.y + dy,
         }
     }
+}
 
-    fn diagonal_right(&self) -> Self {
-        Point {
-            x: self.x + 1,
-            y: self.y + 1,
-        }
-    }
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    InvalidPoint(usize, String),
 }
 
-impl From<&str> for Point {
-    fn from(s: &str) -> Self {
-        let split = s.trim().split(",").collect::<Vec<_>>();
-        Point {
-            x: split[0].parse().unwrap(),
-            y: split[1].parse().unwrap(),
+fn parse_point(line_number: usize, s: &str) -> Result<Point, ParseError> {
+    let invalid = || ParseError::InvalidPoint(line_number, s.to_string());
+    let (x, y) = s.trim().split_once(',').ok_or_else(invalid)?;
+    Ok(Point {
+        x: x.trim().parse().map_err(|_| invalid())?,
+        y: y.trim().parse().map_err(|_| invalid())?,
+    })
+}
+
+const SOURCE: Point = Point { x: 500, y: 0 };
+
+/// Every point on the segment from `a` to `b`, both included. Segments that
+/// are not horizontal, vertical or diagonal are rasterised with Bresenham's
+/// algorithm, always starting at the smaller point so a reversed segment
+/// covers the same points.
+fn segment_points(a: &Point, b: &Point) -> Vec<Point> {
+    let (start, end) = if (a.x, a.y) <= (b.x, b.y) {
+        (a, b)
+    } else {
+        (b, a)
+    };
+    let dx = (end.x - start.x).abs();
+    let dy = -(end.y - start.y).abs();
+    let sx = (end.x - start.x).signum();
+    let sy = (end.y - start.y).signum();
+    let mut error = dx + dy;
+    let mut current = start.clone();
+    let mut points = vec![current.clone()];
+    while current != *end {
+        let e2 = 2 * error;
+        if e2 >= dy {
+            error += dy;
+            current.x += sx;
+        }
+        if e2 <= dx {
+            error += dx;
+            current.y += sy;
         }
+        points.push(current.clone());
+    }
+    if start != a {
+        points.reverse();
     }
+    points
 }
 
-const SOURCE: Point = Point { x: 500, y: 0 };
+/// Points of a rock path like `498,4 -> 498,6 -> 496,6`. The end point of
+/// a segment is only listed once, not again as start of the next segment.
+/// Splitting always yields at least one corner, an empty line is an invalid point.
+pub fn parse_rock_path(line_number: usize, line: &str) -> Result<Vec<Point>, ParseError> {
+    let corners = line
+        .split("->")
+        .map(|p| parse_point(line_number, p))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut points = vec![corners[0].clone()];
+    for w in corners.windows(2) {
+        points.extend(segment_points(&w[0], &w[1]).into_iter().skip(1));
+    }
+    Ok(points)
+}
 
-fn line_to_points(l: &str) -> Vec<Point> {
-    l.split(" -> ")
-        .map(|p| Point::from(p))
-        .collect::<Vec<_>>()
-        .windows(2)
-        .flat_map(|w| {
-            let a = &w[0];
-            let b = &w[1];
-            if a.x != b.x {
-                let diffx = b.x - a.x;
-                let range = min(diffx, 0)..max(1, diffx + 1);
-                range
-                    .map(|p| Point { x: a.x + p, y: a.y })
-                    .collect::<Vec<_>>()
-            } else if a.y != b.y {
-                let diffy = b.y - a.y;
-                let range = min(diffy, 0)..max(1, diffy);
-                range
-                    .map(|p| Point { x: a.x, y: a.y + p })
-                    .collect::<Vec<_>>()
-            } else {
-                vec![a.clone()]
-            }
-        })
-        .collect::<Vec<_>>()
+pub fn parse_rocks(input: &str) -> Result<HashSet<Point>, ParseError> {
+    let mut rocks = HashSet::new();
+    for (i, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        rocks.extend(parse_rock_path(i + 1, line)?);
+    }
+    Ok(rocks)
 }
+
 #[aoc_generator(day14)]
 pub fn input_generator(input: &str) -> HashSet<Point> {
-    let lines = input.lines().collect::<Vec<&str>>();
-    let all_points = lines
-        .into_iter()
-        .flat_map(|l| line_to_points(l))
-        .collect::<Vec<_>>();
-    HashSet::from_iter(all_points.into_iter())
+    parse_rocks(input).unwrap()
 }
 
-fn next_position(points_concrete: &HashSet<Point>, point: &Point) -> Option<Point> {
-    let possible_moves = vec![point.down(), point.diagonal_left(), point.diagonal_right()];
-    possible_moves
-        .into_iter()
-        .find(|m| points_concrete.get(m).is_none())
+#[derive(Debug, PartialEq, Eq)]
+pub enum CaveError {
+    NoSources,
+    NoFallOffsets,
+    /// Every offset has to move the grain down, otherwise it could move forever.
+    InvalidFallOffset((i32, i32)),
 }
 
-#[aoc(day14, part1)]
-pub fn solve_part1(input: &HashSet<Point>) -> u32 {
-    let mut points_concrete = input.clone();
-    let max_y = input.iter().map(|p| p.y).max().unwrap();
-    let mut count_units = 0;
-
-    loop {
-        let mut current_unit = SOURCE.clone();
-        let mut next_move_possible = true;
-        while next_move_possible {
-            match next_position(&points_concrete, &current_unit) {
-                Some(p) => current_unit = p,
-                None => next_move_possible = false,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Floor {
+    None,
+    At(i32),
+    /// The given number of rows below the lowest rock.
+    BelowLowestRock(i32),
+}
+
+/// What happens once a grain falls past the lowest rock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Abyss {
+    /// All sources stop, as in the puzzle.
+    StopAll,
+    /// Only the source the grain came from stops, the others keep pouring.
+    StopSource,
+}
+
+#[derive(Debug, Clone)]
+pub struct SandRules {
+    pub sources: Vec<Point>,
+    /// Moves a grain tries in order, the first free one is taken.
+    pub fall_offsets: Vec<(i32, i32)>,
+    pub floor: Floor,
+    pub abyss: Abyss,
+}
+
+impl Default for SandRules {
+    /// The rules of part 1: one source at `500,0`, falling down, down-left and down-right.
+    fn default() -> Self {
+        SandRules {
+            sources: vec![SOURCE],
+            fall_offsets: vec![(0, 1), (-1, 1), (1, 1)],
+            floor: Floor::None,
+            abyss: Abyss::StopAll,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cell {
+    Air,
+    Rock,
+    Sand,
+}
+
+enum Grain {
+    Rested,
+    Fell,
+    Blocked,
+}
+
+/// Dense map of the cave, wide enough that sand can never leave it
+/// sideways. The floor is not stored, every cell at its depth counts as rock.
+pub struct Cave {
+    min_x: i32,
+    min_y: i32,
+    width: usize,
+    max_rock_y: i32,
+    floor: Option<i32>,
+    rules: SandRules,
+    cells: Vec<Cell>,
+}
+
+impl Cave {
+    pub fn new(rocks: &HashSet<Point>, rules: &SandRules) -> Result<Self, CaveError> {
+        if rules.sources.is_empty() {
+            return Err(CaveError::NoSources);
+        }
+        if rules.fall_offsets.is_empty() {
+            return Err(CaveError::NoFallOffsets);
+        }
+        if let Some(o) = rules.fall_offsets.iter().find(|(_, dy)| *dy <= 0) {
+            return Err(CaveError::InvalidFallOffset(*o));
+        }
+        let all_points = || rocks.iter().chain(rules.sources.iter());
+        let max_rock_y = rocks.iter().map(|p| p.y).max().unwrap_or(0);
+        let floor = match rules.floor {
+            Floor::None => None,
+            Floor::At(y) => Some(y),
+            Floor::BelowLowestRock(d) => Some(max_rock_y + d),
+        };
+        let min_y = all_points().map(|p| p.y).min().unwrap();
+        // sources below the rocks get a row too, their grains start there
+        let max_source_y = rules.sources.iter().map(|p| p.y).max().unwrap();
+        let max_y = floor.unwrap_or(max_rock_y + 1).max(max_source_y);
+        // a grain moves down with every step, so it can't get further sideways than this
+        let max_dx = rules
+            .fall_offsets
+            .iter()
+            .map(|(dx, _)| dx.abs())
+            .max()
+            .unwrap();
+        let spread = max_dx * (max_y - min_y);
+        let min_x = all_points().map(|p| p.x).min().unwrap() - spread - 1;
+        let max_x = all_points().map(|p| p.x).max().unwrap() + spread + 1;
+        let width = (max_x - min_x + 1) as usize;
+        let mut cave = Cave {
+            min_x,
+            min_y,
+            width,
+            max_rock_y,
+            floor,
+            rules: rules.clone(),
+            cells: vec![Cell::Air; width * (max_y - min_y + 1) as usize],
+        };
+        for rock in rocks.iter().filter(|r| r.y <= max_y) {
+            let index = cave.index(rock);
+            cave.cells[index] = Cell::Rock;
+        }
+        Ok(cave)
+    }
+
+    fn index(&self, p: &Point) -> usize {
+        (p.y - self.min_y) as usize * self.width + (p.x - self.min_x) as usize
+    }
+
+    fn is_free(&self, p: &Point) -> bool {
+        self.floor.is_none_or(|f| p.y < f) && self.cells[self.index(p)] == Cell::Air
+    }
+
+    fn falls_into_abyss(&self, p: &Point) -> bool {
+        self.floor.is_none() && p.y > self.max_rock_y
+    }
+
+    /// Drops one grain along `path`, the path of the previous grain from the
+    /// same source. Everything on that path up to the first cell taken in the
+    /// meantime is unchanged, so the grain can start from there. Only grains
+    /// of other sources can take cells of the path.
+    fn drop_grain(&mut self, path: &mut Vec<Point>) -> Grain {
+        if self.rules.sources.len() > 1 {
+            if let Some(taken) = path.iter().position(|p| !self.is_free(p)) {
+                path.truncate(taken);
+            }
+        }
+        while let Some(current) = path.last() {
+            let next = self
+                .rules
+                .fall_offsets
+                .iter()
+                .map(|o| current.moved(*o))
+                .find(|p| self.falls_into_abyss(p) || self.is_free(p));
+            match next {
+                Some(p) if self.falls_into_abyss(&p) => return Grain::Fell,
+                Some(p) => path.push(p),
+                None => {
+                    let index = self.index(current);
+                    self.cells[index] = Cell::Sand;
+                    path.pop();
+                    return Grain::Rested;
+                }
             }
-            if current_unit.y > max_y {
-                return count_units;
+        }
+        Grain::Blocked
+    }
+
+    /// Pours one grain from every active source in turn until every source
+    /// is blocked or stopped by the abyss. Returns the number of grains that
+    /// came to rest per source.
+    pub fn pour(&mut self) -> Vec<u32> {
+        let sources = self.rules.sources.clone();
+        let mut paths = sources
+            .iter()
+            .map(|s| {
+                if self.falls_into_abyss(s) || self.is_free(s) {
+                    vec![s.clone()]
+                } else {
+                    vec![]
+                }
+            })
+            .collect::<Vec<_>>();
+        let mut active = vec![true; sources.len()];
+        let mut grains = vec![0; sources.len()];
+        while active.iter().any(|a| *a) {
+            for (i, path) in paths.iter_mut().enumerate() {
+                if !active[i] {
+                    continue;
+                }
+                match self.drop_grain(path) {
+                    Grain::Rested => grains[i] += 1,
+                    Grain::Blocked => active[i] = false,
+                    Grain::Fell if self.rules.abyss == Abyss::StopAll => return grains,
+                    Grain::Fell => active[i] = false,
+                }
             }
         }
-        points_concrete.insert(current_unit.clone());
-        count_units += 1;
+        grains
+    }
+
+    /// Highest resting grain of sand per column, as `(x, y)` ordered by `x`.
+    pub fn heightmap(&self) -> Vec<(i32, i32)> {
+        (0..self.width)
+            .filter_map(|column| {
+                self.cells
+                    .iter()
+                    .skip(column)
+                    .step_by(self.width)
+                    .position(|c| *c == Cell::Sand)
+                    .map(|row| (self.min_x + column as i32, self.min_y + row as i32))
+            })
+            .collect()
     }
 }
 
-fn horizontal_line_at_bottom(input: &HashSet<Point>) -> HashSet<Point> {
-    let max_y = input.iter().map(|p| p.y).max().unwrap();
-    (0..1000)
-        .map(|i| Point { x: i, y: max_y + 2 })
-        .collect::<HashSet<_>>()
+#[aoc(day14, part1)]
+pub fn solve_part1(input: &HashSet<Point>) -> u32 {
+    Cave::new(input, &SandRules::default())
+        .unwrap()
+        .pour()
+        .iter()
+        .sum()
 }
 
 #[aoc(day14, part2)]
 pub fn solve_part2(input: &HashSet<Point>) -> u32 {
-    let mut points_concrete = input
-        .union(&horizontal_line_at_bottom(&input))
-        .map(|p| p.clone())
-        .collect::<HashSet<_>>();
-    let mut count_units = 1;
-
-    loop {
-        let mut current_unit = SOURCE.clone();
-        let mut next_move_possible = true;
-        while next_move_possible {
-            match next_position(&points_concrete, &current_unit) {
-                Some(p) => current_unit = p,
-                None => next_move_possible = false,
-            }
-            if current_unit.y == 0 {
-                return count_units;
-            }
-        }
-        points_concrete.insert(current_unit.clone());
-        count_units += 1;
+    let rules = SandRules {
+        floor: Floor::BelowLowestRock(2),
+        ..SandRules::default()
+    };
+    Cave::new(input, &rules).unwrap().pour().iter().sum()
+}
+
+pub struct Day14;
+
+impl_solution! {
+    Day14 {
+        day: 14,
+        input: HashSet<Point>,
+        parse: input_generator,
+        part1: solve_part1,
+        part2: solve_part2,
     }
 }
 
@@ -209,4 +412,173 @@ mod tests {
 
         assert_eq!(result, 93);
     }
+
+    #[test]
+    fn it_should_spread_beyond_fixed_width() {
+        // a single rock deep down, the sand pile reaches x < 0 and x > 1000
+        let input = input_generator("500,600 -> 500,600");
+
+        let result = solve_part2(&input);
+
+        assert_eq!(result, 602 * 602 - 1);
+    }
+
+    #[test]
+    fn it_should_pour_from_multiple_sources() {
+        let input = input_generator("498,2 -> 502,2");
+        let rules = SandRules {
+            sources: vec![Point::new(499, 0), Point::new(501, 0)],
+            abyss: Abyss::StopSource,
+            ..SandRules::default()
+        };
+        let mut cave = Cave::new(&input, &rules).unwrap();
+
+        let grains = cave.pour();
+        // both first grains rest above the rock, the second grain of the left
+        // source rolls off to the left, so the right one gets the cell between
+        // them before its third grain rolls off to the right
+        //   ...011...
+        //   ..#####..
+        assert_eq!(grains, vec![1, 2]);
+        assert_eq!(cave.cells.iter().filter(|c| **c == Cell::Sand).count(), 3);
+
+        let input = input_generator("490,5 -> 510,5");
+        let with_floor = SandRules {
+            sources: vec![Point::new(495, 0), Point::new(505, 0)],
+            floor: Floor::At(3),
+            ..rules
+        };
+        let grains = Cave::new(&input, &with_floor).unwrap().pour();
+        // two separate triangles of three rows, the source cells included
+        assert_eq!(grains, vec![9, 9]);
+    }
+
+    #[test]
+    fn it_should_stop_all_sources_on_abyss() {
+        let input = input_generator("498,2 -> 502,2");
+        let rules = SandRules {
+            sources: vec![Point::new(500, 0), Point::new(600, 0)],
+            ..SandRules::default()
+        };
+        assert_eq!(Cave::new(&input, &rules).unwrap().pour(), vec![1, 0]);
+
+        let rules = SandRules {
+            abyss: Abyss::StopSource,
+            ..rules
+        };
+        assert_eq!(Cave::new(&input, &rules).unwrap().pour(), vec![4, 0]);
+    }
+
+    #[test]
+    fn it_should_pour_from_sources_below_the_rocks() {
+        let input = input_generator("498,2 -> 502,2");
+        let rules = SandRules {
+            sources: vec![Point::new(500, 0), Point::new(600, 5)],
+            abyss: Abyss::StopSource,
+            ..SandRules::default()
+        };
+        assert_eq!(Cave::new(&input, &rules).unwrap().pour(), vec![4, 0]);
+    }
+
+    #[test]
+    fn it_should_use_custom_fall_offsets() {
+        let input = input_generator("495,3 -> 505,3");
+        let rules = SandRules {
+            fall_offsets: vec![(0, 1)],
+            ..SandRules::default()
+        };
+        let mut cave = Cave::new(&input, &rules).unwrap();
+        assert_eq!(cave.pour(), vec![3]);
+        assert_eq!(cave.heightmap(), vec![(500, 0)]);
+
+        let rules = SandRules {
+            fall_offsets: vec![(0, 1), (2, 1)],
+            ..SandRules::default()
+        };
+        let mut cave = Cave::new(&input, &rules).unwrap();
+        assert_eq!(cave.pour(), vec![3]);
+        assert_eq!(cave.heightmap(), vec![(500, 1), (502, 2)]);
+
+        assert_eq!(
+            Cave::new(
+                &input,
+                &SandRules {
+                    fall_offsets: vec![(1, 0)],
+                    ..SandRules::default()
+                }
+            )
+            .err(),
+            Some(CaveError::InvalidFallOffset((1, 0)))
+        );
+    }
+
+    #[test]
+    fn it_should_compute_heightmap() {
+        let input = input_generator(EXAMPLE_INPUT);
+        let mut cave = Cave::new(&input, &SandRules::default()).unwrap();
+        cave.pour();
+
+        let heightmap = cave.heightmap();
+        assert_eq!(heightmap.first(), Some(&(495, 8)));
+        assert_eq!(heightmap.iter().find(|(x, _)| *x == 500), Some(&(500, 2)));
+        assert_eq!(heightmap.last(), Some(&(501, 3)));
+    }
+
+    #[test]
+    fn it_should_rasterise_reversed_and_zero_length_segments() {
+        assert_eq!(
+            parse_rock_path(1, "498,6 -> 498,4").unwrap(),
+            vec![Point::new(498, 6), Point::new(498, 5), Point::new(498, 4)]
+        );
+        assert_eq!(
+            parse_rock_path(1, "498,4 -> 496,4").unwrap(),
+            vec![Point::new(498, 4), Point::new(497, 4), Point::new(496, 4)]
+        );
+        assert_eq!(
+            parse_rock_path(1, "5,5 -> 5,5").unwrap(),
+            vec![Point::new(5, 5)]
+        );
+        assert_eq!(parse_rock_path(1, "5,5").unwrap(), vec![Point::new(5, 5)]);
+        assert_eq!(
+            parse_rock_path(1, "5,5 -> 5,6 -> 5,6 -> 5,5").unwrap(),
+            vec![Point::new(5, 5), Point::new(5, 6), Point::new(5, 5)]
+        );
+    }
+
+    #[test]
+    fn it_should_rasterise_diagonal_segments() {
+        assert_eq!(
+            parse_rock_path(1, "0,0 -> 3,3").unwrap(),
+            vec![
+                Point::new(0, 0),
+                Point::new(1, 1),
+                Point::new(2, 2),
+                Point::new(3, 3)
+            ]
+        );
+        let forward = parse_rock_path(1, "0,0 -> 4,2").unwrap();
+        assert_eq!(forward.len(), 5);
+        let mut backward = parse_rock_path(1, "4,2 -> 0,0").unwrap();
+        backward.reverse();
+        assert_eq!(forward, backward);
+        assert!(forward
+            .windows(2)
+            .all(|w| (w[1].x - w[0].x).abs() <= 1 && (w[1].y - w[0].y).abs() <= 1));
+    }
+
+    #[test]
+    fn it_should_report_malformed_points() {
+        assert_eq!(
+            parse_rocks("1,1 -> 1,3\n1,x -> 2,2"),
+            Err(ParseError::InvalidPoint(2, "1,x ".to_string()))
+        );
+        assert_eq!(
+            parse_rocks("1,1 -> 13"),
+            Err(ParseError::InvalidPoint(1, " 13".to_string()))
+        );
+        assert_eq!(
+            parse_rocks("1,1 -> "),
+            Err(ParseError::InvalidPoint(1, " ".to_string()))
+        );
+    }
 }
@@ -1,4 +1,6 @@
-use std::{collections::HashSet, ops::{Range}, cmp::{min, max}};
+use crate::context::{Interrupted, SolveContext};
+use crate::solution::impl_solution;
+use std::{collections::HashSet, ops::{Range, RangeInclusive}, cmp::{min, max}};
 use range_ext::intersect::Intersect;
 use sscanf::sscanf;
 #[derive(Hash, Clone, PartialEq, Eq, Debug)]
@@ -60,7 +62,7 @@ impl Sensor {
     }
 }
 
-fn solve_part_1_for_y(input: &[Sensor], y: i32) -> usize {
+pub fn solve_part_1_for_y(input: &[Sensor], y: i32) -> usize {
     let points_within_distance_to_beacons = input
         .into_iter()
         .flat_map(|s| s.points_within_distance_to_beacon_on_y(y)).collect::<HashSet<_>>();
@@ -131,31 +133,101 @@ fn merge_ranges(input: &Vec<Range<i32>>) -> Vec<Range<i32>> {
     merged_ranges
 }
 
-fn solve_part_2_for_y(input: &[Sensor], min_y: i32, max_y: i32) -> usize {
+pub fn solve_part_2_for_y(input: &[Sensor], min_y: i32, max_y: i32) -> usize {
+    solve_part_2_for_y_with(input, min_y, max_y, &SolveContext::new()).unwrap()
+}
+
+/// Checks the rows in order, reporting each one as a step of progress.
+pub fn solve_part_2_for_y_with(
+    input: &[Sensor],
+    min_y: i32,
+    max_y: i32,
+    context: &SolveContext,
+) -> Result<usize, Interrupted> {
     let complete_range = min_y..(max_y + 1);
-    let result_list = (min_y..=max_y).collect::<HashSet<_>>().iter().map(|y| {
+    context.start((max_y - min_y + 1) as u64);
+    first_gap(input, &complete_range, min_y..=max_y, context)
+        .unwrap_or_else(|| panic!("no free position between {} and {}", min_y, max_y))
+}
 
-        let ranges = input
-            .into_iter()
-            .map(|s| s.range_within_distance_to_beacon_on_y(*y)).collect::<Vec<_>>();
+#[cfg(not(feature = "parallel"))]
+fn first_gap(
+    input: &[Sensor],
+    complete_range: &Range<i32>,
+    rows: RangeInclusive<i32>,
+    context: &SolveContext,
+) -> Option<Result<usize, Interrupted>> {
+    first_gap_in_order(input, complete_range, rows, context)
+}
 
+fn first_gap_in_order(
+    input: &[Sensor],
+    complete_range: &Range<i32>,
+    rows: RangeInclusive<i32>,
+    context: &SolveContext,
+) -> Option<Result<usize, Interrupted>> {
+    for y in rows {
+        if let Err(interrupted) = context.check() {
+            return Some(Err(interrupted));
+        }
+        if let Some(frequency) = tuning_frequency_on_y(input, complete_range, y) {
+            return Some(Ok(frequency));
+        }
+        context.advance(1);
+    }
+    None
+}
 
-        let merged_ranges = &merge_ranges_as_long_as_shrinking(&ranges);
+/// Rows handed to a core at once. A task per row costs more than checking it.
+#[cfg(feature = "parallel")]
+const ROWS_PER_TASK: i32 = 4096;
+
+/// Chunks of rows are checked on all cores, each chunk in order, and the gap
+/// of the lowest chunk wins, as it would sequentially. Areas of fewer than
+/// two chunks are not split.
+#[cfg(feature = "parallel")]
+fn first_gap(
+    input: &[Sensor],
+    complete_range: &Range<i32>,
+    rows: RangeInclusive<i32>,
+    context: &SolveContext,
+) -> Option<Result<usize, Interrupted>> {
+    use rayon::prelude::*;
+    let (first, last) = (*rows.start(), *rows.end());
+    if last - first < 2 * ROWS_PER_TASK {
+        return first_gap_in_order(input, complete_range, rows, context);
+    }
+    (0..=(last - first) / ROWS_PER_TASK)
+        .into_par_iter()
+        .map(|chunk| first + chunk * ROWS_PER_TASK)
+        .find_map_first(|start| {
+            let end = (start + ROWS_PER_TASK - 1).min(last);
+            first_gap_in_order(input, complete_range, start..=end, context)
+        })
+}
 
-        if !merged_ranges.iter().any(|r| complete_range.intersect(r).is_within()) {
-            //dbg!(y);
-            dbg!(&merged_ranges);
-            let all_points_set = merged_ranges.iter().flat_map(|r| r.clone().into_iter().collect::<Vec<_>>()).collect::<HashSet<_>>();
-            let mut sorted = all_points_set.iter().collect::<Vec<_>>();
-            sorted.sort();
-            if let Some(missing) = sorted.windows(2).find(|s| *s[0] + 1 != *s[1]) {
-                dbg!(missing);
-                return Some(((missing[0] + 1) as usize) * 4000000 + *y as usize);
-            }
-        };
-        return None;
-    }).filter(|s| s.is_some()).map(|s| s.unwrap()).collect::<Vec<_>>();
-    *result_list.first().unwrap()
+fn tuning_frequency_on_y(input: &[Sensor], complete_range: &Range<i32>, y: i32) -> Option<usize> {
+    let ranges = input
+        .iter()
+        .map(|s| s.range_within_distance_to_beacon_on_y(y))
+        .collect::<Vec<_>>();
+
+    let merged_ranges = &merge_ranges_as_long_as_shrinking(&ranges);
+
+    if !merged_ranges.iter().any(|r| complete_range.intersect(r).is_within()) {
+        tracing::debug!(y, ?merged_ranges, "row is not covered completely");
+        let all_points_set = merged_ranges
+            .iter()
+            .flat_map(|r| r.clone().into_iter().collect::<Vec<_>>())
+            .collect::<HashSet<_>>();
+        let mut sorted = all_points_set.iter().collect::<Vec<_>>();
+        sorted.sort();
+        if let Some(missing) = sorted.windows(2).find(|s| *s[0] + 1 != *s[1]) {
+            tracing::debug!(y, ?missing, "found the gap");
+            return Some(((missing[0] + 1) as usize) * 4000000 + y as usize);
+        }
+    };
+    None
 }
 
 #[aoc_generator(day15)]
@@ -173,6 +245,19 @@ pub fn solve_part2(input: &[Sensor]) -> usize {
     solve_part_2_for_y(input, 0, 4000000)
 }
 
+pub struct Day15;
+
+impl_solution! {
+    Day15 {
+        day: 15,
+        input: Vec<Sensor>,
+        parse: input_generator,
+        part1: solve_part1,
+        part2: solve_part2,
+        part2_with: |input, context| solve_part_2_for_y_with(input, 0, 4000000, context),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -237,6 +322,16 @@ mod tests {
         assert_eq!(result, 56000011)
     }
 
+    #[test]
+    fn it_should_time_out_part2() {
+        let input = input_generator(EXAMPLE_INPUT);
+        let context = SolveContext::new().with_budget(std::time::Duration::ZERO);
+
+        let result = solve_part_2_for_y_with(&input, 0, 20, &context);
+
+        assert_eq!(result, Err(Interrupted::TimedOut))
+    }
+
     const EXAMPLE_INPUT: &str = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15
 Sensor at x=9, y=16: closest beacon is at x=10, y=16
 Sensor at x=13, y=2: closest beacon is at x=15, y=3
@@ -1,258 +1,672 @@
-use enum_iterator::{all, Sequence};
-use std::str::FromStr;
+use crate::solution::impl_solution;
+use std::collections::HashMap;
 
-pub struct Round {
-    you: Sign,
-    opponent: Sign,
+#[derive(Debug, PartialEq, Eq)]
+pub enum GameError {
+    InvalidRelation(String),
+    InvalidScores(String),
+    InvalidLetters(String),
+    InvalidOpponent(String),
+    InvalidLine(String),
+    /// No sign gets the outcome against this opponent.
+    UnreachableOutcome(Sign, Outcome),
 }
 
-impl Round {
-    fn score(&self) -> u32 {
-        self.outcome().score() + self.you.sign_score()
-    }
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+pub struct Sign(usize);
 
-    fn outcome(&self) -> Outcome {
-        match self.you {
-            Sign::ROCK if self.opponent.looses_against() == Sign::ROCK => Outcome::WIN,
-            Sign::PAPER if self.opponent.looses_against() == Sign::PAPER => Outcome::WIN,
-            Sign::SCISSORS if self.opponent.looses_against() == Sign::SCISSORS => Outcome::WIN,
-            _ if self.you == self.opponent => Outcome::DRAW,
-            _ => Outcome::LOOSE,
-        }
+impl Sign {
+    pub const ROCK: Sign = Sign(0);
+    pub const PAPER: Sign = Sign(1);
+    pub const SCISSORS: Sign = Sign(2);
+    pub const SPOCK: Sign = Sign(3);
+    pub const LIZARD: Sign = Sign(4);
+
+    pub fn index(&self) -> usize {
+        self.0
     }
 }
 
-enum Outcome {
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+pub enum Outcome {
     WIN,
     LOOSE,
     DRAW,
 }
 
-impl Outcome {
-    fn score(&self) -> u32 {
-        match self {
-            Self::WIN => 6,
-            Self::LOOSE => 0,
-            Self::DRAW => 3,
-        }
-    }
+#[derive(Debug, PartialEq, Eq)]
+pub struct Round {
+    you: Sign,
+    opponent: Sign,
 }
 
-impl FromStr for Outcome {
-    type Err = ();
+#[derive(Debug, PartialEq, Eq)]
+pub struct RoundPart2 {
+    opponent: Sign,
+    outcome: Outcome,
+}
 
-    fn from_str(input: &str) -> Result<Self, Self::Err> {
-        match input {
-            "X" => Ok(Outcome::LOOSE),
-            "Y" => Ok(Outcome::DRAW),
-            "Z" => Ok(Outcome::WIN),
-            _ => Err(()),
+#[derive(Debug, Clone, Copy)]
+pub struct OutcomeScores {
+    pub win: u32,
+    pub draw: u32,
+    pub loose: u32,
+}
+
+impl OutcomeScores {
+    fn score(&self, outcome: Outcome) -> u32 {
+        match outcome {
+            Outcome::WIN => self.win,
+            Outcome::LOOSE => self.loose,
+            Outcome::DRAW => self.draw,
         }
     }
 }
-pub struct RoundPart2 {
-    opponent: Sign,
-    outcome: Outcome,
+
+/// How the opponent picks signs when analysing a strategy guide.
+#[derive(Debug, Clone)]
+pub enum Opponent {
+    Random,
+    /// Relative weight per sign, in the order of the game's signs.
+    Biased(Vec<f64>),
 }
 
-impl RoundPart2 {
-    fn score(&self) -> u32 {
-        let own_sign = self.own_sign();
-        let round = Round {
-            opponent: self.opponent,
-            you: own_sign,
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrategyReport {
+    pub rounds: usize,
+    /// Score if the opponent plays exactly what the guide predicts.
+    pub guide_score: u32,
+    pub expected_score: f64,
+    pub expected_score_per_round: f64,
+}
+
+/// A game of rock-paper-scissors with any number of signs.
+/// `beats[a][b]` is true if sign `a` wins against sign `b`.
+#[derive(Debug, Clone)]
+pub struct Game {
+    signs: Vec<String>,
+    beats: Vec<Vec<bool>>,
+    sign_scores: Vec<u32>,
+    outcome_scores: OutcomeScores,
+    opponent_letters: HashMap<String, Sign>,
+    own_letters: HashMap<String, Sign>,
+    outcome_letters: HashMap<String, Outcome>,
+}
+
+impl Game {
+    /// Builds a game from an explicit list of `(winner, looser)` pairs.
+    /// Every two different signs have to appear in exactly one pair.
+    pub fn new(signs: &[&str], wins: &[(&str, &str)]) -> Result<Self, GameError> {
+        let n = signs.len();
+        if n < 2 {
+            return Err(GameError::InvalidRelation(format!(
+                "need at least two signs, got {}",
+                n
+            )));
+        }
+        let index_of = |name: &str| {
+            signs
+                .iter()
+                .position(|s| *s == name)
+                .ok_or_else(|| GameError::InvalidRelation(format!("unknown sign {}", name)))
+        };
+        let mut beats = vec![vec![false; n]; n];
+        for (winner, looser) in wins {
+            let w = index_of(winner)?;
+            let l = index_of(looser)?;
+            if w == l || beats[w][l] || beats[l][w] {
+                return Err(GameError::InvalidRelation(format!(
+                    "{} against {} is defined twice or against itself",
+                    winner, looser
+                )));
+            }
+            beats[w][l] = true;
+        }
+        for a in 0..n {
+            for b in (a + 1)..n {
+                if !beats[a][b] && !beats[b][a] {
+                    return Err(GameError::InvalidRelation(format!(
+                        "no winner between {} and {}",
+                        signs[a], signs[b]
+                    )));
+                }
+            }
+        }
+
+        let game = Game {
+            signs: signs.iter().map(|s| s.to_string()).collect(),
+            beats,
+            sign_scores: (1..=n as u32).collect(),
+            outcome_scores: OutcomeScores {
+                win: 6,
+                draw: 3,
+                loose: 0,
+            },
+            opponent_letters: HashMap::new(),
+            own_letters: HashMap::new(),
+            outcome_letters: HashMap::from([
+                ("X".to_string(), Outcome::LOOSE),
+                ("Y".to_string(), Outcome::DRAW),
+                ("Z".to_string(), Outcome::WIN),
+            ]),
+        };
+        if n > 26 {
+            return Ok(game);
+        }
+        let letters = |first: u8| {
+            (0..n)
+                .map(|i| ((first + i as u8) as char).to_string())
+                .collect::<Vec<_>>()
         };
-        round.outcome().score() + own_sign.sign_score()
+        let opponent_letters = letters(b'A');
+        let own_letters = letters(b'Z' + 1 - n as u8);
+        game.with_opponent_letters(
+            &opponent_letters
+                .iter()
+                .map(|s| s.as_str())
+                .collect::<Vec<_>>(),
+        )?
+        .with_own_letters(&own_letters.iter().map(|s| s.as_str()).collect::<Vec<_>>())
     }
 
-    fn own_sign(&self) -> Sign {
-        match self.outcome {
-            Outcome::DRAW => self.opponent,
-            Outcome::LOOSE => self.opponent.wins_against(),
-            Outcome::WIN => self.opponent.looses_against(),
+    /// Builds the balanced cyclic game where each sign beats the signs an odd
+    /// number of steps before it, e.g. rock, paper, scissors, spock, lizard.
+    pub fn cyclic(signs: &[&str]) -> Result<Self, GameError> {
+        let n = signs.len();
+        if n < 3 || n.is_multiple_of(2) {
+            return Err(GameError::InvalidRelation(format!(
+                "a cyclic game needs an odd number of at least three signs, got {}",
+                n
+            )));
         }
+        let wins = (0..n)
+            .flat_map(|w| (0..n).map(move |l| (w, l)))
+            .filter(|(w, l)| (w + n - l) % n % 2 == 1)
+            .map(|(w, l)| (signs[w], signs[l]))
+            .collect::<Vec<_>>();
+        Game::new(signs, &wins)
     }
-}
 
-#[derive(PartialEq, Eq, Clone, Copy, Debug, Sequence)]
-pub enum Sign {
-    ROCK,
-    PAPER,
-    SCISSORS,
-}
+    pub fn classic() -> Self {
+        Game::cyclic(&["rock", "paper", "scissors"]).unwrap()
+    }
 
-impl Sign {
-    fn sign_score(&self) -> u32 {
-        match self {
-            Self::ROCK => 1,
-            Self::PAPER => 2,
-            Self::SCISSORS => 3,
+    pub fn rock_paper_scissors_lizard_spock() -> Self {
+        Game::cyclic(&["rock", "paper", "scissors", "spock", "lizard"]).unwrap()
+    }
+
+    pub fn with_sign_scores(mut self, scores: &[u32]) -> Result<Self, GameError> {
+        if scores.len() != self.signs.len() {
+            return Err(GameError::InvalidScores(format!(
+                "expected {} sign scores, got {}",
+                self.signs.len(),
+                scores.len()
+            )));
         }
+        self.sign_scores = scores.to_vec();
+        Ok(self)
     }
 
-    fn wins_against(&self) -> Sign {
-        match self {
-            Self::ROCK => Self::SCISSORS,
-            Self::PAPER => Self::ROCK,
-            Self::SCISSORS => Self::PAPER,
+    pub fn with_outcome_scores(mut self, scores: OutcomeScores) -> Self {
+        self.outcome_scores = scores;
+        self
+    }
+
+    /// Letters the opponent column uses, in the order of the signs.
+    pub fn with_opponent_letters(mut self, letters: &[&str]) -> Result<Self, GameError> {
+        self.opponent_letters = self.letter_mapping(letters)?;
+        Ok(self)
+    }
+
+    /// Letters the second column uses when it is read as a sign, in the order of the signs.
+    pub fn with_own_letters(mut self, letters: &[&str]) -> Result<Self, GameError> {
+        self.own_letters = self.letter_mapping(letters)?;
+        Ok(self)
+    }
+
+    /// Letters the second column uses when it is read as loose, draw and win.
+    pub fn with_outcome_letters(mut self, letters: [&str; 3]) -> Result<Self, GameError> {
+        if letters.iter().any(|l| l.is_empty()) || distinct(&letters) != 3 {
+            return Err(GameError::InvalidLetters(format!(
+                "outcome letters must be distinct and not empty: {:?}",
+                letters
+            )));
         }
+        self.outcome_letters = HashMap::from([
+            (letters[0].to_string(), Outcome::LOOSE),
+            (letters[1].to_string(), Outcome::DRAW),
+            (letters[2].to_string(), Outcome::WIN),
+        ]);
+        Ok(self)
     }
 
-    fn looses_against(&self) -> Self {
-        let wins = self.wins_against();
-        for sign in all::<Self>().collect::<Vec<_>>() {
-            if sign != wins && sign != *self {
-                return sign;
-            }
+    fn letter_mapping(&self, letters: &[&str]) -> Result<HashMap<String, Sign>, GameError> {
+        if letters.len() != self.signs.len()
+            || letters.iter().any(|l| l.is_empty())
+            || distinct(letters) != letters.len()
+        {
+            return Err(GameError::InvalidLetters(format!(
+                "expected {} distinct letters, got {:?}",
+                self.signs.len(),
+                letters
+            )));
         }
-        panic!("")
+        Ok(letters
+            .iter()
+            .enumerate()
+            .map(|(i, l)| (l.to_string(), Sign(i)))
+            .collect())
     }
-}
 
-impl FromStr for Sign {
-    type Err = ();
+    pub fn signs(&self) -> impl Iterator<Item = Sign> {
+        (0..self.signs.len()).map(Sign)
+    }
+
+    pub fn name(&self, sign: Sign) -> &str {
+        &self.signs[sign.0]
+    }
+
+    pub fn sign_score(&self, sign: Sign) -> u32 {
+        self.sign_scores[sign.0]
+    }
+
+    pub fn outcome_score(&self, outcome: Outcome) -> u32 {
+        self.outcome_scores.score(outcome)
+    }
+
+    pub fn wins_against(&self, a: Sign, b: Sign) -> bool {
+        self.beats[a.0][b.0]
+    }
 
-    fn from_str(input: &str) -> Result<Self, Self::Err> {
-        match input {
-            "A" | "X" => Ok(Sign::ROCK),
-            "B" | "Y" => Ok(Sign::PAPER),
-            "C" | "Z" => Ok(Sign::SCISSORS),
-            _ => Err(()),
+    pub fn outcome(&self, you: Sign, opponent: Sign) -> Outcome {
+        if you == opponent {
+            Outcome::DRAW
+        } else if self.wins_against(you, opponent) {
+            Outcome::WIN
+        } else {
+            Outcome::LOOSE
         }
     }
-}
 
-impl FromStr for Round {
-    type Err = ();
+    pub fn score(&self, round: &Round) -> u32 {
+        self.outcome_score(self.outcome(round.you, round.opponent)) + self.sign_score(round.you)
+    }
 
-    fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let mut split = input.split_whitespace();
-        let opponent = Sign::from_str(split.next().unwrap())?;
-        let you = Sign::from_str(split.next().unwrap())?;
-        Ok(Round { you, opponent })
+    /// The sign to play against `opponent` to get `outcome`. If several signs
+    /// lead to the outcome the one with the highest score is chosen.
+    pub fn own_sign(&self, opponent: Sign, outcome: Outcome) -> Result<Sign, GameError> {
+        self.signs()
+            .filter(|s| self.outcome(*s, opponent) == outcome)
+            .fold(None, |best: Option<Sign>, s| match best {
+                Some(b) if self.sign_score(b) >= self.sign_score(s) => Some(b),
+                _ => Some(s),
+            })
+            .ok_or(GameError::UnreachableOutcome(opponent, outcome))
     }
-}
 
-impl FromStr for RoundPart2 {
-    type Err = ();
+    pub fn score_part2(&self, round: &RoundPart2) -> Result<u32, GameError> {
+        Ok(self.score(&Round {
+            you: self.own_sign(round.opponent, round.outcome)?,
+            opponent: round.opponent,
+        }))
+    }
+
+    fn columns<'a>(&self, line: &'a str) -> Result<(&'a str, &'a str), GameError> {
+        let mut split = line.split_whitespace();
+        match (split.next(), split.next(), split.next()) {
+            (Some(a), Some(b), None) => Ok((a, b)),
+            _ => Err(GameError::InvalidLine(line.to_string())),
+        }
+    }
 
-    fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let mut split = input.split_whitespace();
-        let opponent = Sign::from_str(split.next().unwrap())?;
-        let outcome = Outcome::from_str(split.next().unwrap())?;
-        Ok(RoundPart2 { opponent, outcome })
+    fn lookup<T: Copy>(map: &HashMap<String, T>, letter: &str, line: &str) -> Result<T, GameError> {
+        map.get(letter)
+            .copied()
+            .ok_or_else(|| GameError::InvalidLine(line.to_string()))
     }
+
+    pub fn parse_round(&self, line: &str) -> Result<Round, GameError> {
+        let (opponent, you) = self.columns(line)?;
+        Ok(Round {
+            opponent: Self::lookup(&self.opponent_letters, opponent, line)?,
+            you: Self::lookup(&self.own_letters, you, line)?,
+        })
+    }
+
+    pub fn parse_round_part2(&self, line: &str) -> Result<RoundPart2, GameError> {
+        let (opponent, outcome) = self.columns(line)?;
+        Ok(RoundPart2 {
+            opponent: Self::lookup(&self.opponent_letters, opponent, line)?,
+            outcome: Self::lookup(&self.outcome_letters, outcome, line)?,
+        })
+    }
+
+    pub fn parse_guide(&self, input: &str) -> Result<Vec<Round>, GameError> {
+        input
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(|l| self.parse_round(l))
+            .collect()
+    }
+
+    pub fn parse_guide_part2(&self, input: &str) -> Result<Vec<RoundPart2>, GameError> {
+        input
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(|l| self.parse_round_part2(l))
+            .collect()
+    }
+
+    fn probabilities(&self, opponent: &Opponent) -> Result<Vec<f64>, GameError> {
+        let n = self.signs.len();
+        match opponent {
+            Opponent::Random => Ok(vec![1.0 / n as f64; n]),
+            Opponent::Biased(weights) => {
+                let total: f64 = weights.iter().sum();
+                if weights.len() != n
+                    || weights.iter().any(|w| !w.is_finite() || *w < 0.0)
+                    || total <= 0.0
+                {
+                    return Err(GameError::InvalidOpponent(format!(
+                        "expected {} non negative weights, got {:?}",
+                        n, weights
+                    )));
+                }
+                Ok(weights.iter().map(|w| w / total).collect())
+            }
+        }
+    }
+
+    fn report(&self, guide_score: u32, expected: Vec<f64>) -> StrategyReport {
+        let rounds = expected.len();
+        let expected_score: f64 = expected.iter().sum();
+        StrategyReport {
+            rounds,
+            guide_score,
+            expected_score,
+            expected_score_per_round: if rounds == 0 {
+                0.0
+            } else {
+                expected_score / rounds as f64
+            },
+        }
+    }
+
+    /// Expected score when you play the signs of the guide but the opponent
+    /// picks its sign according to `opponent` instead of following the guide.
+    pub fn analyse(
+        &self,
+        guide: &[Round],
+        opponent: &Opponent,
+    ) -> Result<StrategyReport, GameError> {
+        let probabilities = self.probabilities(opponent)?;
+        let expected = guide
+            .iter()
+            .map(|r| {
+                self.signs()
+                    .map(|o| {
+                        probabilities[o.0]
+                            * self.score(&Round {
+                                you: r.you,
+                                opponent: o,
+                            }) as f64
+                    })
+                    .sum()
+            })
+            .collect();
+        let guide_score = guide.iter().map(|r| self.score(r)).sum();
+        Ok(self.report(guide_score, expected))
+    }
+
+    /// Expected score when you react to the opponent's actual sign to get the
+    /// outcome the guide asks for, with the opponent picking according to `opponent`.
+    pub fn analyse_part2(
+        &self,
+        guide: &[RoundPart2],
+        opponent: &Opponent,
+    ) -> Result<StrategyReport, GameError> {
+        let probabilities = self.probabilities(opponent)?;
+        let expected = guide
+            .iter()
+            .map(|r| {
+                self.signs()
+                    .map(|o| {
+                        self.score_part2(&RoundPart2 {
+                            opponent: o,
+                            outcome: r.outcome,
+                        })
+                        .map(|score| probabilities[o.0] * score as f64)
+                    })
+                    .sum::<Result<f64, _>>()
+            })
+            .collect::<Result<_, _>>()?;
+        let guide_score = guide
+            .iter()
+            .map(|r| self.score_part2(r))
+            .sum::<Result<_, _>>()?;
+        Ok(self.report(guide_score, expected))
+    }
+}
+
+fn distinct(letters: &[&str]) -> usize {
+    let mut sorted = letters.to_vec();
+    sorted.sort();
+    sorted.dedup();
+    sorted.len()
 }
 
 #[aoc_generator(day2, part1)]
 pub fn input_generator(input: &str) -> Vec<Round> {
-    let lines = input.lines().collect::<Vec<&str>>();
-    lines.iter().map(|l| Round::from_str(l).unwrap()).collect()
+    Game::classic().parse_guide(input).unwrap()
 }
 
 #[aoc_generator(day2, part2)]
 pub fn input_generator2(input: &str) -> Vec<RoundPart2> {
-    let lines = input.lines().collect::<Vec<&str>>();
-    lines
-        .iter()
-        .map(|l| RoundPart2::from_str(l).unwrap())
-        .collect()
+    Game::classic().parse_guide_part2(input).unwrap()
 }
 
 #[aoc(day2, part1)]
 pub fn solve_part1(input: &[Round]) -> u32 {
-    input.iter().map(|r| r.score()).sum()
+    let game = Game::classic();
+    input.iter().map(|r| game.score(r)).sum()
 }
 
 #[aoc(day2, part2)]
 pub fn solve_part2(input: &[RoundPart2]) -> u32 {
-    input.iter().map(|r| r.score()).sum()
+    let game = Game::classic();
+    input
+        .iter()
+        .map(|r| game.score_part2(r))
+        .sum::<Result<_, _>>()
+        .unwrap()
+}
+
+pub struct Day2;
+
+impl_solution! {
+    Day2 {
+        day: 2,
+        input: (Vec<Round>, Vec<RoundPart2>),
+        parse: |input: &str| (input_generator(input), input_generator2(input)),
+        part1: |input: &Self::Input| solve_part1(&input.0),
+        part2: |input: &Self::Input| solve_part2(&input.1),
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{FromStr, Outcome, Round, RoundPart2, Sign};
+    use super::*;
+
+    const EXAMPLE_INPUT: &str = "A Y\nB X\nC Z";
+
     #[test]
     fn it_should_compute_sign_score() {
-        assert_eq!(Sign::ROCK.sign_score(), 1);
-        assert_eq!(Sign::PAPER.sign_score(), 2);
-        assert_eq!(Sign::SCISSORS.sign_score(), 3);
+        let game = Game::classic();
+        assert_eq!(game.sign_score(Sign::ROCK), 1);
+        assert_eq!(game.sign_score(Sign::PAPER), 2);
+        assert_eq!(game.sign_score(Sign::SCISSORS), 3);
     }
 
     #[test]
     fn it_should_compute_round_score_win() {
+        let game = Game::classic();
         let round = Round {
             you: Sign::PAPER,
             opponent: Sign::ROCK,
         };
-        assert_eq!(round.outcome().score(), 6);
-        assert_eq!(
-            round.score(),
-            round.outcome().score() + round.you.sign_score()
-        );
+        assert_eq!(game.outcome(round.you, round.opponent), Outcome::WIN);
+        assert_eq!(game.score(&round), 6 + game.sign_score(round.you));
     }
 
     #[test]
     fn it_should_compute_round_score_draw() {
+        let game = Game::classic();
         let round = Round {
             you: Sign::ROCK,
             opponent: Sign::ROCK,
         };
-        assert_eq!(round.outcome().score(), 3);
-        assert_eq!(
-            round.score(),
-            round.outcome().score() + round.you.sign_score()
-        );
+        assert_eq!(game.outcome(round.you, round.opponent), Outcome::DRAW);
+        assert_eq!(game.score(&round), 3 + game.sign_score(round.you));
     }
 
     #[test]
     fn it_should_compute_round_score_loose() {
+        let game = Game::classic();
         let round = Round {
             you: Sign::ROCK,
             opponent: Sign::PAPER,
         };
-        assert_eq!(round.outcome().score(), 0);
-        assert_eq!(
-            round.score(),
-            round.outcome().score() + round.you.sign_score()
-        );
+        assert_eq!(game.outcome(round.you, round.opponent), Outcome::LOOSE);
+        assert_eq!(game.score(&round), game.sign_score(round.you));
     }
 
     #[test]
     fn it_should_parse_sign() {
-        let line = "A X";
-        let round = Round::from_str(line).unwrap();
+        let round = Game::classic().parse_round("A X").unwrap();
         assert_eq!(round.you, Sign::ROCK);
         assert_eq!(round.opponent, Sign::ROCK);
     }
 
     #[test]
     fn it_score_round_2_loose() {
-        let line = "A X";
-        let round = RoundPart2::from_str(line).unwrap();
+        let game = Game::classic();
+        let round = game.parse_round_part2("A X").unwrap();
         assert_eq!(
-            round.score(),
-            Outcome::LOOSE.score() + Sign::SCISSORS.sign_score()
+            game.score_part2(&round).unwrap(),
+            game.outcome_score(Outcome::LOOSE) + game.sign_score(Sign::SCISSORS)
         );
     }
 
     #[test]
     fn it_score_round_2_win() {
-        let line = "B Z";
-        let round = RoundPart2::from_str(line).unwrap();
+        let game = Game::classic();
+        let round = game.parse_round_part2("B Z").unwrap();
         assert_eq!(
-            round.score(),
-            Outcome::WIN.score() + Sign::SCISSORS.sign_score()
+            game.score_part2(&round).unwrap(),
+            game.outcome_score(Outcome::WIN) + game.sign_score(Sign::SCISSORS)
         );
     }
 
     #[test]
     fn it_score_round_2_draw() {
-        let line = "C Y";
-        let round = RoundPart2::from_str(line).unwrap();
+        let game = Game::classic();
+        let round = game.parse_round_part2("C Y").unwrap();
+        assert_eq!(
+            game.score_part2(&round).unwrap(),
+            game.outcome_score(Outcome::DRAW) + game.sign_score(Sign::SCISSORS)
+        );
+    }
+
+    #[test]
+    fn it_should_solve_example() {
+        assert_eq!(solve_part1(&input_generator(EXAMPLE_INPUT)), 15);
+        assert_eq!(solve_part2(&input_generator2(EXAMPLE_INPUT)), 12);
+    }
+
+    #[test]
+    fn it_should_play_lizard_spock() {
+        let game = Game::rock_paper_scissors_lizard_spock();
+        assert!(game.wins_against(Sign::ROCK, Sign::LIZARD));
+        assert!(game.wins_against(Sign::ROCK, Sign::SCISSORS));
+        assert!(game.wins_against(Sign::SPOCK, Sign::ROCK));
+        assert!(game.wins_against(Sign::SPOCK, Sign::SCISSORS));
+        assert!(game.wins_against(Sign::LIZARD, Sign::SPOCK));
+        assert!(game.wins_against(Sign::LIZARD, Sign::PAPER));
+        assert!(game.wins_against(Sign::PAPER, Sign::SPOCK));
+        assert!(game.wins_against(Sign::SCISSORS, Sign::LIZARD));
+        for a in game.signs() {
+            let wins = game.signs().filter(|b| game.wins_against(a, *b)).count();
+            assert_eq!(wins, 2, "{}", game.name(a));
+        }
+    }
+
+    #[test]
+    fn it_should_reject_invalid_relations() {
+        assert!(Game::cyclic(&["a", "b", "c", "d"]).is_err());
+        assert!(Game::new(&["a", "b", "c"], &[("a", "b"), ("b", "c")]).is_err());
+        assert!(Game::new(&["a", "b"], &[("a", "b"), ("b", "a")]).is_err());
+        assert!(Game::new(&["a", "b"], &[("a", "b")]).is_ok());
+    }
+
+    #[test]
+    fn it_should_report_unreachable_outcomes() {
+        let game = Game::new(&["a", "b"], &[("a", "b")]).unwrap();
+        let round = game.parse_round_part2("A Z").unwrap();
+
+        assert_eq!(game.own_sign(Sign::PAPER, Outcome::WIN), Ok(Sign::ROCK));
+        assert_eq!(
+            game.own_sign(Sign::PAPER, Outcome::LOOSE),
+            Err(GameError::UnreachableOutcome(Sign::PAPER, Outcome::LOOSE))
+        );
+        assert_eq!(
+            game.score_part2(&round),
+            Err(GameError::UnreachableOutcome(Sign::ROCK, Outcome::WIN))
+        );
+    }
+
+    #[test]
+    fn it_should_use_custom_letters_and_scores() {
+        let game = Game::classic()
+            .with_opponent_letters(&["R", "P", "S"])
+            .unwrap()
+            .with_own_letters(&["r", "p", "s"])
+            .unwrap()
+            .with_sign_scores(&[10, 20, 30])
+            .unwrap()
+            .with_outcome_scores(OutcomeScores {
+                win: 2,
+                draw: 1,
+                loose: 0,
+            });
+        let guide = game.parse_guide("R p\nS p").unwrap();
+        assert_eq!(guide.iter().map(|r| game.score(r)).sum::<u32>(), 22 + 20);
         assert_eq!(
-            round.score(),
-            Outcome::DRAW.score() + Sign::SCISSORS.sign_score()
+            game.parse_round("A X"),
+            Err(GameError::InvalidLine("A X".to_string()))
         );
+        assert!(Game::classic().with_own_letters(&["X", "X", "Z"]).is_err());
+    }
+
+    #[test]
+    fn it_should_analyse_strategy() {
+        let game = Game::classic();
+        let guide = game.parse_guide(EXAMPLE_INPUT).unwrap();
+
+        let random = game.analyse(&guide, &Opponent::Random).unwrap();
+        assert_eq!(random.rounds, 3);
+        assert_eq!(random.guide_score, 15);
+        assert!((random.expected_score - 15.0).abs() < 1e-9);
+
+        let always_rock = game
+            .analyse(&guide, &Opponent::Biased(vec![1.0, 0.0, 0.0]))
+            .unwrap();
+        assert!((always_rock.expected_score - (8.0 + 4.0 + 3.0)).abs() < 1e-9);
+
+        let guide2 = game.parse_guide_part2(EXAMPLE_INPUT).unwrap();
+        let random2 = game.analyse_part2(&guide2, &Opponent::Random).unwrap();
+        assert_eq!(random2.guide_score, 12);
+        assert!((random2.expected_score - (3.0 + 0.0 + 6.0 + 3.0 * 2.0)).abs() < 1e-9);
+
+        assert!(game.analyse(&guide, &Opponent::Biased(vec![1.0])).is_err());
     }
 }
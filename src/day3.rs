@@ -1,17 +1,20 @@
-use std::collections::HashSet;
+use crate::solution::impl_solution;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum RucksackError {
+    InvalidItem(char),
+    OddNumberOfItems(String),
+    InvalidGroupSize(usize),
+    IncompleteGroup(usize),
+    NoBadge(usize),
+    AmbiguousBadge(usize, Vec<char>),
+}
 
 #[aoc_generator(day3, part1)]
 pub fn input_generator(input: &str) -> Vec<Rucksack> {
-    let compartment_tuples = input
+    input
         .lines()
-        .map(|l| l.split_at(l.len() / 2))
-        .collect::<Vec<_>>();
-    compartment_tuples
-        .iter()
-        .map(|(l, r)| Rucksack {
-            compartment1: String::from(*l),
-            compartment2: String::from(*r),
-        })
+        .map(|l| Rucksack::try_from(l).unwrap())
         .collect::<Vec<_>>()
 }
 
@@ -26,86 +29,177 @@ pub fn solve_part1(input: &[Rucksack]) -> u32 {
 
 #[aoc(day3, part2)]
 pub fn solve_part2(input: &str) -> u32 {
-    let lines = input.lines().collect::<Vec<_>>();
-    let groups = lines.len() / 3;
-    let mut lines_iter = lines.iter();
-    let s = (0..groups)
-        .map(|_| {
-            let set1 = lines_iter.next().unwrap().chars().collect::<HashSet<_>>();
-            let set2 = lines_iter.next().unwrap().chars().collect::<HashSet<_>>();
-            let set3 = lines_iter.next().unwrap().chars().collect::<HashSet<_>>();
-            let intersect1 = set1.intersection(&set2).collect::<HashSet<_>>();
-            let intersect2 = set2.intersection(&set3).collect::<HashSet<_>>();
-            let common = intersect1
-                .intersection(&intersect2)
-                .map(|i| *i)
-                .collect::<Vec<_>>();
-            score(&common)
-        })
-        .sum();
-    s
+    groups(input, 3)
+        .unwrap()
+        .iter()
+        .map(|g| priority(g.badge().unwrap()).unwrap())
+        .sum()
+}
+
+/// Priority of an item: `a` to `z` are 1 to 26, `A` to `Z` are 27 to 52.
+pub fn priority(item: char) -> Result<u32, RucksackError> {
+    match item {
+        'a'..='z' => Ok(item as u32 - 'a' as u32 + 1),
+        'A'..='Z' => Ok(item as u32 - 'A' as u32 + 27),
+        _ => Err(RucksackError::InvalidItem(item)),
+    }
+}
+
+fn item_for_priority(priority: u32) -> char {
+    match priority {
+        1..=26 => (b'a' + (priority - 1) as u8) as char,
+        _ => (b'A' + (priority - 27) as u8) as char,
+    }
+}
+
+/// Set of items as a bitmask, where bit `n` stands for the item with priority `n`.
+pub fn items_mask(items: &str) -> Result<u64, RucksackError> {
+    items
+        .chars()
+        .try_fold(0u64, |mask, c| Ok(mask | 1 << priority(c)?))
+}
+
+fn items_in_mask(mask: u64) -> Vec<char> {
+    (1..=52)
+        .filter(|p| mask & (1 << p) != 0)
+        .map(item_for_priority)
+        .collect()
+}
+
+fn mask_score(mask: u64) -> u32 {
+    (1..=52).filter(|p| mask & (1 << p) != 0).sum()
 }
 
 #[derive(Debug)]
 pub struct Rucksack {
-    compartment1: String,
-    compartment2: String,
+    compartment1: u64,
+    compartment2: u64,
+}
+
+impl TryFrom<&str> for Rucksack {
+    type Error = RucksackError;
+
+    fn try_from(line: &str) -> Result<Self, Self::Error> {
+        // only ASCII letters are items, so the halves below split between characters
+        if let Some(item) = line.chars().find(|c| !c.is_ascii_alphabetic()) {
+            return Err(RucksackError::InvalidItem(item));
+        }
+        if !line.len().is_multiple_of(2) {
+            return Err(RucksackError::OddNumberOfItems(line.to_string()));
+        }
+        let (l, r) = line.split_at(line.len() / 2);
+        Ok(Rucksack {
+            compartment1: items_mask(l)?,
+            compartment2: items_mask(r)?,
+        })
+    }
 }
 
 impl Rucksack {
+    fn items(&self) -> u64 {
+        self.compartment1 | self.compartment2
+    }
+
+    pub fn common_items(&self) -> Vec<char> {
+        items_in_mask(self.compartment1 & self.compartment2)
+    }
+
     fn score(&self) -> u32 {
-        let set1 = self.compartment1.chars().collect::<HashSet<_>>();
-        let set2 = self.compartment2.chars().collect::<HashSet<_>>();
-        let common_items = set1.intersection(&set2).collect::<Vec<_>>();
-        score(&common_items)
-    }
-}
-
-fn score(items: &Vec<&char>) -> u32 {
-    let alphabet = (b'a'..=b'z')
-        .chain(b'A'..=b'Z')
-        .filter_map(|c| {
-            let c = c as char; // Convert to char
-            if c.is_alphabetic() {
-                Some(c)
-            } else {
-                None
-            } // Filter only alphabetic chars
+        mask_score(self.compartment1 & self.compartment2)
+    }
+}
+
+#[derive(Debug)]
+pub struct Group {
+    index: usize,
+    members: Vec<Rucksack>,
+}
+
+impl Group {
+    /// Items carried by every member of the group.
+    pub fn badge_candidates(&self) -> Vec<char> {
+        items_in_mask(self.candidates_mask())
+    }
+
+    fn candidates_mask(&self) -> u64 {
+        self.members.iter().fold(u64::MAX, |a, r| a & r.items())
+    }
+
+    pub fn badge(&self) -> Result<char, RucksackError> {
+        match self.badge_candidates().as_slice() {
+            [] => Err(RucksackError::NoBadge(self.index)),
+            [badge] => Ok(*badge),
+            candidates => Err(RucksackError::AmbiguousBadge(
+                self.index,
+                candidates.to_vec(),
+            )),
+        }
+    }
+}
+
+/// Splits the rucksacks into consecutive groups of `size` elves.
+pub fn groups(input: &str, size: usize) -> Result<Vec<Group>, RucksackError> {
+    if size == 0 {
+        return Err(RucksackError::InvalidGroupSize(size));
+    }
+    let rucksacks = input
+        .lines()
+        .map(Rucksack::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+    if !rucksacks.len().is_multiple_of(size) {
+        return Err(RucksackError::IncompleteGroup(rucksacks.len() / size));
+    }
+    let mut rucksacks = rucksacks.into_iter();
+    Ok((0..rucksacks.len() / size)
+        .map(|index| Group {
+            index,
+            members: rucksacks.by_ref().take(size).collect(),
         })
-        .collect::<Vec<_>>();
-    items.iter().map(|i| score_item(i, &alphabet)).sum()
+        .collect())
 }
 
-fn score_item(item: &char, alphabet: &Vec<char>) -> u32 {
-    match alphabet.iter().position(|c| c == item) {
-        Some(p) => (p + 1).try_into().unwrap(),
-        None => panic!("char not found"),
+pub struct Day3;
+
+impl_solution! {
+    Day3 {
+        day: 3,
+        input: (Vec<Rucksack>, String),
+        parse: |input: &str| (input_generator(input), input.to_string()),
+        part1: |input: &Self::Input| solve_part1(&input.0),
+        part2: |input: &Self::Input| solve_part2(&input.1),
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{input_generator, solve_part2, Rucksack};
+    use super::*;
     #[test]
     fn it_should_parse_input() {
         let result = input_generator("jNNBMTNzvTqhQLhQLMQL\nVCwnVRCGHHJTdsLtrdhrGdsq");
         assert_eq!(result.len(), 2);
 
         let first = result.first().unwrap();
-        assert_eq!(first.compartment1, "jNNBMTNzvT");
-        assert_eq!(first.compartment2, "qhQLhQLMQL");
+        assert_eq!(first.compartment1, items_mask("jNNBMTNzvT").unwrap());
+        assert_eq!(first.compartment2, items_mask("qhQLhQLMQL").unwrap());
     }
 
     #[test]
     fn it_should_compute_score() {
-        let b = Rucksack {
-            compartment1: "jNNBMTNzvT".to_string(),
-            compartment2: "qhQLhQLMQL".to_string(),
-        };
+        let b = Rucksack::try_from("jNNBMTNzvTqhQLhQLMQL").unwrap();
 
+        assert_eq!(b.common_items(), vec!['M']);
         assert_eq!(b.score(), 39);
     }
 
+    #[test]
+    fn it_should_compute_priority() {
+        assert_eq!(priority('a'), Ok(1));
+        assert_eq!(priority('z'), Ok(26));
+        assert_eq!(priority('A'), Ok(27));
+        assert_eq!(priority('Z'), Ok(52));
+        assert_eq!(priority('1'), Err(RucksackError::InvalidItem('1')));
+    }
+
     #[test]
     fn it_should_solve_part2_1() {
         let score = solve_part2("AB\nAC\nAD");
@@ -126,4 +220,52 @@ mod tests {
 
         assert_eq!(score, 27 + 2);
     }
+
+    #[test]
+    fn it_should_use_all_members_of_a_group() {
+        let groups = groups("abcx\nabcy\nazcw", 3).unwrap();
+
+        assert_eq!(groups[0].badge_candidates(), vec!['a', 'c']);
+        assert_eq!(
+            groups[0].badge(),
+            Err(RucksackError::AmbiguousBadge(0, vec!['a', 'c']))
+        );
+
+        let groups = super::groups("abcx\nabcy\nazqw", 3).unwrap();
+        assert_eq!(groups[0].badge(), Ok('a'));
+    }
+
+    #[test]
+    fn it_should_support_any_group_size() {
+        let groups = groups("aB\naC\naD\naE\nzB\nyC", 2).unwrap();
+
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[0].badge(), Ok('a'));
+        assert_eq!(groups[1].badge(), Ok('a'));
+        assert_eq!(groups[2].badge(), Err(RucksackError::NoBadge(2)));
+    }
+
+    #[test]
+    fn it_should_report_invalid_input() {
+        assert_eq!(
+            groups("aa\nbb", 3).unwrap_err(),
+            RucksackError::IncompleteGroup(0)
+        );
+        assert_eq!(
+            groups("aa\nb1", 2).unwrap_err(),
+            RucksackError::InvalidItem('1')
+        );
+        assert_eq!(
+            groups("aa", 0).unwrap_err(),
+            RucksackError::InvalidGroupSize(0)
+        );
+        assert_eq!(
+            Rucksack::try_from("abc").unwrap_err(),
+            RucksackError::OddNumberOfItems("abc".to_string())
+        );
+        assert_eq!(
+            Rucksack::try_from("aé").unwrap_err(),
+            RucksackError::InvalidItem('é')
+        );
+    }
 }
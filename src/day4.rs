@@ -1,3 +1,5 @@
+use crate::solution::impl_solution;
+
 #[aoc_generator(day4, par1)]
 pub fn input_generator(input: &str) -> Vec<(Vec<u32>, Vec<u32>)> {
     input.lines().map(|l| parse_line(l)).collect::<Vec<_>>()
@@ -59,6 +61,18 @@ fn contains_any(a: &Vec<u32>, b: &Vec<u32>) -> bool {
     a.iter().any(|i| b.contains(i))
 }
 
+pub struct Day4;
+
+impl_solution! {
+    Day4 {
+        day: 4,
+        input: Vec<(Vec<u32>, Vec<u32>)>,
+        parse: input_generator,
+        part1: solve_part1,
+        part2: solve_part2,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::day4::solve_part1;
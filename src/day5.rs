@@ -1,114 +1,347 @@
+use crate::normalize::Normalization;
+use crate::solution::impl_solution;
 use sscanf::sscanf;
 use std::collections::VecDeque;
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    MissingLabels,
+    InvalidLabel(String),
+    DuplicateLabel(usize),
+    InvalidDrawing(usize, String),
+    InvalidMove(usize, String),
+}
 
 #[derive(Debug)]
 pub struct SupplyStacks {
+    ids: Vec<usize>,
     stacks: Vec<VecDeque<char>>,
     moves: Vec<Move>,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Move {
-    count: u8,
-    from: u8,
-    to: u8,
+    count: usize,
+    from: usize,
+    to: usize,
 }
-#[aoc_generator(day5)]
-pub fn input_generator(input: &str) -> SupplyStacks {
-    let lines = input.lines().collect::<Vec<&str>>();
-    let stacks_lines = lines
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum MoveError {
+    UnknownStack {
+        step: usize,
+        id: usize,
+    },
+    Underflow {
+        step: usize,
+        id: usize,
+        available: usize,
+        requested: usize,
+    },
+}
+
+/// Moves crates between stacks. The crates are taken from the top of a stack,
+/// top crate first, and the crane decides in which order they end up on the target.
+pub trait Crane {
+    fn place(&self, taken: Vec<char>, target: &mut VecDeque<char>);
+}
+
+/// Moves one crate at a time, so the moved crates end up reversed.
+pub struct CrateMover9000;
+
+/// Moves all crates at once, keeping their order.
+pub struct CrateMover9001;
+
+/// Moves up to `capacity` crates at once, keeping the order within each lift.
+pub struct CapacityCrane {
+    capacity: usize,
+}
+
+impl CapacityCrane {
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "crane capacity must be at least 1");
+        CapacityCrane { capacity }
+    }
+}
+
+impl Crane for CrateMover9000 {
+    fn place(&self, taken: Vec<char>, target: &mut VecDeque<char>) {
+        CapacityCrane::new(1).place(taken, target)
+    }
+}
+
+impl Crane for CrateMover9001 {
+    fn place(&self, taken: Vec<char>, target: &mut VecDeque<char>) {
+        taken.into_iter().rev().for_each(|c| target.push_front(c));
+    }
+}
+
+impl Crane for CapacityCrane {
+    fn place(&self, taken: Vec<char>, target: &mut VecDeque<char>) {
+        for lift in taken.chunks(self.capacity) {
+            lift.iter().rev().for_each(|c| target.push_front(*c));
+        }
+    }
+}
+
+/// Stacks at one point of the rearrangement, the front of each stack is its top.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StackState {
+    ids: Vec<usize>,
+    stacks: Vec<VecDeque<char>>,
+}
+
+impl StackState {
+    /// Top crate of every stack that is not empty.
+    pub fn tops(&self) -> String {
+        self.stacks.iter().filter_map(|s| s.front()).collect()
+    }
+}
+
+impl fmt::Display for StackState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .ids
+            .iter()
+            .map(|i| i.to_string().len())
+            .max()
+            .unwrap_or(1)
+            .max(3);
+        let height = self.stacks.iter().map(|s| s.len()).max().unwrap_or(0);
+        for level in (0..height).rev() {
+            let line = self
+                .stacks
+                .iter()
+                .map(
+                    |s| match s.len().checked_sub(level + 1).and_then(|i| s.get(i)) {
+                        Some(c) => format!("{:^width$}", format!("[{}]", c)),
+                        None => " ".repeat(width),
+                    },
+                )
+                .collect::<Vec<_>>()
+                .join(" ");
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        let labels = self
+            .ids
+            .iter()
+            .map(|i| format!("{:^width$}", i))
+            .collect::<Vec<_>>()
+            .join(" ");
+        writeln!(f, "{}", labels.trim_end())
+    }
+}
+
+impl SupplyStacks {
+    fn stack_index(&self, id: usize) -> Option<usize> {
+        self.ids.iter().position(|i| *i == id)
+    }
+
+    /// Applies all moves with the given crane. Returns the initial state
+    /// followed by the state after every move.
+    pub fn apply<C: Crane>(&self, crane: &C) -> Result<Vec<StackState>, MoveError> {
+        let mut state = StackState {
+            ids: self.ids.clone(),
+            stacks: self.stacks.clone(),
+        };
+        let mut history = vec![state.clone()];
+        for (step, m) in self.moves.iter().enumerate() {
+            let index = |id: usize| {
+                self.stack_index(id)
+                    .ok_or(MoveError::UnknownStack { step, id })
+            };
+            let from = index(m.from)?;
+            let to = index(m.to)?;
+            let available = state.stacks[from].len();
+            if available < m.count {
+                return Err(MoveError::Underflow {
+                    step,
+                    id: m.from,
+                    available,
+                    requested: m.count,
+                });
+            }
+            let taken = state.stacks[from].drain(..m.count).collect::<Vec<_>>();
+            crane.place(taken, &mut state.stacks[to]);
+            history.push(state.clone());
+        }
+        Ok(history)
+    }
+}
+
+/// Label of a stack together with the columns it spans in the drawing.
+struct Label {
+    id: usize,
+    start: usize,
+    end: usize,
+}
+
+impl Label {
+    fn distance(&self, column: usize) -> usize {
+        self.start.saturating_sub(column) + column.saturating_sub(self.end)
+    }
+}
+
+fn parse_labels(line: &str) -> Result<Vec<Label>, ParseError> {
+    let chars = line.chars().collect::<Vec<_>>();
+    let mut labels: Vec<Label> = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i].is_whitespace() {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < chars.len() && !chars[i].is_whitespace() {
+            i += 1;
+        }
+        let token = chars[start..i].iter().collect::<String>();
+        let id = token
+            .parse::<usize>()
+            .map_err(|_| ParseError::InvalidLabel(token.clone()))?;
+        if labels.iter().any(|l| l.id == id) {
+            return Err(ParseError::DuplicateLabel(id));
+        }
+        labels.push(Label {
+            id,
+            start,
+            end: i - 1,
+        });
+    }
+    if labels.is_empty() {
+        return Err(ParseError::MissingLabels);
+    }
+    Ok(labels)
+}
+
+/// Crates of a drawing line as `(column of the letter, letter)`.
+fn parse_crates(line_number: usize, line: &str) -> Result<Vec<(usize, char)>, ParseError> {
+    let invalid = || ParseError::InvalidDrawing(line_number, line.to_string());
+    let chars = line.chars().collect::<Vec<_>>();
+    let mut crates = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            c if c.is_whitespace() => i += 1,
+            '[' => match (chars.get(i + 1), chars.get(i + 2)) {
+                (Some(c), Some(']')) if !c.is_whitespace() => {
+                    crates.push((i + 1, *c));
+                    i += 3;
+                }
+                _ => return Err(invalid()),
+            },
+            _ => return Err(invalid()),
+        }
+    }
+    Ok(crates)
+}
+
+fn parse_move(line_number: usize, line: &str) -> Result<Move, ParseError> {
+    sscanf!(line.trim(), "move {usize} from {usize} to {usize}")
+        .map(|(count, from, to)| Move { count, from, to })
+        .map_err(|_| ParseError::InvalidMove(line_number, line.to_string()))
+}
+
+/// Parses a drawing of crate stacks followed by a blank line and the moves.
+/// The number of stacks and their ids are taken from the label line at the
+/// bottom of the drawing, each crate belongs to the label closest to it.
+pub fn parse(input: &str) -> Result<SupplyStacks, ParseError> {
+    let lines = input.lines().collect::<Vec<_>>();
+    let drawing_end = lines
         .iter()
-        .take(8)
-        .map(|s| s.chars().collect::<Vec<_>>())
-        .map(|s| {
-            vec![
-                s.get(1).unwrap().clone(),
-                s.get(5).unwrap().clone(),
-                s.get(9).unwrap().clone(),
-                s.get(13).unwrap().clone(),
-                s.get(17).unwrap().clone(),
-                s.get(21).unwrap().clone(),
-                s.get(25).unwrap().clone(),
-                s.get(29).unwrap().clone(),
-                s.get(33).unwrap().clone(),
-            ]
-        })
-        .collect::<Vec<_>>();
-    let moves = lines
+        .position(|l| l.trim().is_empty())
+        .unwrap_or(lines.len());
+    let label_line = lines[..drawing_end]
         .iter()
-        .skip(10)
-        .map(|l| sscanf!(l, "move {u8} from {u8} to {u8}").unwrap())
-        .map(|(count, from, to)| Move { count, from, to })
-        .collect::<Vec<_>>();
+        .rposition(|l| !l.trim().is_empty())
+        .ok_or(ParseError::MissingLabels)?;
+    let labels = parse_labels(lines[label_line])?;
 
-    let stacks = (0..9)
-        .map(|i| {
-            stacks_lines
+    let mut stacks = vec![VecDeque::new(); labels.len()];
+    for (line_number, line) in lines.iter().enumerate().take(label_line) {
+        for (column, c) in parse_crates(line_number + 1, line)? {
+            let (stack, _) = labels
                 .iter()
-                .map(|s| *s.get(i).unwrap())
-                .filter(|c| *c != ' ')
-                .collect::<VecDeque<_>>()
-        })
-        .collect::<Vec<_>>();
-    SupplyStacks { stacks, moves }
+                .enumerate()
+                .min_by_key(|(_, l)| l.distance(column))
+                .unwrap();
+            stacks[stack].push_back(c);
+        }
+    }
+
+    let moves = lines
+        .iter()
+        .enumerate()
+        .skip(drawing_end)
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(i, l)| parse_move(i + 1, l))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(SupplyStacks {
+        ids: labels.iter().map(|l| l.id).collect(),
+        stacks,
+        moves,
+    })
+}
+
+#[aoc_generator(day5)]
+pub fn input_generator(input: &str) -> SupplyStacks {
+    parse(input).unwrap()
 }
 
 #[aoc(day5, part1)]
 pub fn solve_part1(input: &SupplyStacks) -> String {
-    let moves = input.moves.clone();
-    let mut stacks = input.stacks.clone();
-
-    for m in moves {
-        (0..m.count).for_each(|_i| {
-            let taken = stacks[(m.from - 1) as usize].pop_front();
-            if let Some(v) = taken {
-                stacks[(m.to - 1) as usize].push_front(v)
-            }
-        })
-    }
-
-    String::from_iter(stacks.iter().map(|s| s.front().unwrap())).replace(" ", "")
+    input.apply(&CrateMover9000).unwrap().last().unwrap().tops()
 }
 
 #[aoc(day5, part2)]
 pub fn solve_part2(input: &SupplyStacks) -> String {
-    let moves = input.moves.clone();
-    let mut stacks = input
-        .stacks
-        .clone()
-        .iter()
-        .map(|v| v.to_owned())
-        .collect::<Vec<_>>();
-
-    for m in moves {
-        let mut moved: VecDeque<char> = VecDeque::new();
-        (0..m.count).for_each(|_i| {
-            let taken = stacks[(m.from - 1) as usize].pop_front();
-            if let Some(t) = taken {
-                moved.push_front(t);
-            }
-        });
-        let target = &mut stacks[(m.to - 1) as usize];
-        moved.iter().for_each(|m| target.push_front(*m));
-    }
+    input.apply(&CrateMover9001).unwrap().last().unwrap().tops()
+}
+
+pub struct Day5;
 
-    String::from_iter(stacks.iter().map(|s| s.front().unwrap())).replace(" ", "")
+impl_solution! {
+    Day5 {
+        day: 5,
+        input: SupplyStacks,
+        parse: input_generator,
+        part1: solve_part1,
+        part2: solve_part2,
+        normalization: Normalization::default().with_dedent(false),
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::day5::{solve_part1, solve_part2, Move, SupplyStacks};
-    use std::collections::VecDeque;
+    use super::*;
+
+    const EXAMPLE_INPUT: &str = "    [D]
+[N] [C]
+[Z] [M] [P]
+ 1   2   3
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2";
 
     #[test]
-    fn it_should_solve_part1() {
-        let stacks = SupplyStacks {
-            stacks: vec![
+    fn it_should_parse_example() {
+        let stacks = parse(EXAMPLE_INPUT).unwrap();
+        assert_eq!(stacks.ids, vec![1, 2, 3]);
+        assert_eq!(
+            stacks.stacks,
+            vec![
                 VecDeque::from(['N', 'Z']),
                 VecDeque::from(['D', 'C', 'M']),
                 VecDeque::from(['P']),
-            ],
-            moves: vec![
+            ]
+        );
+        assert_eq!(
+            stacks.moves,
+            vec![
                 Move {
                     count: 1,
                     from: 2,
@@ -129,44 +362,126 @@ mod tests {
                     from: 1,
                     to: 2,
                 },
-            ],
-        };
-        let result = solve_part1(&stacks);
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_parse_multi_digit_labels() {
+        let drawing = [
+            "                                        [K]",
+            "[A]                                 [J] [L]",
+            "[B] [C] [D] [E] [F] [G] [H] [I]     [M] [N]",
+            " 1   2   3   4   5   6   7   8   9  10  11",
+            "",
+            "move 1 from 11 to 9",
+        ]
+        .join("\n");
+        let stacks = parse(&drawing).unwrap();
+        assert_eq!(stacks.ids, (1..=11).collect::<Vec<_>>());
+        assert_eq!(stacks.stacks[0], VecDeque::from(['A', 'B']));
+        assert!(stacks.stacks[8].is_empty());
+        assert_eq!(stacks.stacks[9], VecDeque::from(['J', 'M']));
+        assert_eq!(stacks.stacks[10], VecDeque::from(['K', 'L', 'N']));
+        assert_eq!(
+            stacks.moves,
+            vec![Move {
+                count: 1,
+                from: 11,
+                to: 9
+            }]
+        );
+    }
+
+    #[test]
+    fn it_should_report_malformed_input() {
+        assert_eq!(
+            parse("[A]\n 1\n\nmove one from 1 to 1").unwrap_err(),
+            ParseError::InvalidMove(4, "move one from 1 to 1".to_string())
+        );
+        assert_eq!(
+            parse("[A\n 1").unwrap_err(),
+            ParseError::InvalidDrawing(1, "[A".to_string())
+        );
+        assert_eq!(
+            parse("[A]\n a").unwrap_err(),
+            ParseError::InvalidLabel("a".to_string())
+        );
+        assert_eq!(
+            parse("[A] [B]\n 1   1").unwrap_err(),
+            ParseError::DuplicateLabel(1)
+        );
+        assert_eq!(parse("").unwrap_err(), ParseError::MissingLabels);
+    }
+
+    #[test]
+    fn it_should_solve_part1() {
+        let result = solve_part1(&input_generator(EXAMPLE_INPUT));
         assert_eq!(result, "CMZ".to_string());
     }
 
     #[test]
     fn it_should_solve_part2() {
-        let stacks = SupplyStacks {
-            stacks: vec![
-                VecDeque::from(['N', 'Z']),
-                VecDeque::from(['D', 'C', 'M']),
-                VecDeque::from(['P']),
-            ],
-            moves: vec![
-                Move {
-                    count: 1,
-                    from: 2,
-                    to: 1,
-                },
-                Move {
-                    count: 3,
-                    from: 1,
-                    to: 3,
-                },
-                Move {
-                    count: 2,
-                    from: 2,
-                    to: 1,
-                },
-                Move {
-                    count: 1,
-                    from: 1,
-                    to: 2,
-                },
-            ],
-        };
-        let result = solve_part2(&stacks);
+        let result = solve_part2(&input_generator(EXAMPLE_INPUT));
         assert_eq!(result, "MCD".to_string());
     }
+
+    #[test]
+    fn it_should_apply_capacity_crane() {
+        let stacks = parse("[A]\n[B]\n[C]\n 1   2\n\nmove 3 from 1 to 2").unwrap();
+
+        let history = stacks.apply(&CapacityCrane::new(2)).unwrap();
+        assert_eq!(
+            history.last().unwrap().stacks[1],
+            VecDeque::from(['C', 'A', 'B'])
+        );
+
+        let history = stacks.apply(&CapacityCrane::new(1)).unwrap();
+        assert_eq!(
+            history.last().unwrap().stacks[1],
+            VecDeque::from(['C', 'B', 'A'])
+        );
+
+        let history = stacks.apply(&CapacityCrane::new(3)).unwrap();
+        assert_eq!(
+            history.last().unwrap().stacks[1],
+            VecDeque::from(['A', 'B', 'C'])
+        );
+    }
+
+    #[test]
+    fn it_should_record_history() {
+        let history = input_generator(EXAMPLE_INPUT)
+            .apply(&CrateMover9000)
+            .unwrap();
+
+        assert_eq!(history.len(), 5);
+        assert_eq!(history[0].tops(), "NDP");
+        assert_eq!(history[1].tops(), "DCP");
+        assert_eq!(history[2].tops(), "CZ");
+        assert_eq!(
+            history[1].to_string(),
+            "[D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3\n"
+        );
+    }
+
+    #[test]
+    fn it_should_report_invalid_moves() {
+        let stacks = parse("[A]\n 1   2\n\nmove 2 from 1 to 2").unwrap();
+        assert_eq!(
+            stacks.apply(&CrateMover9001).unwrap_err(),
+            MoveError::Underflow {
+                step: 0,
+                id: 1,
+                available: 1,
+                requested: 2
+            }
+        );
+
+        let stacks = parse("[A]\n 1   2\n\nmove 1 from 1 to 2\nmove 1 from 2 to 3").unwrap();
+        assert_eq!(
+            stacks.apply(&CrateMover9000).unwrap_err(),
+            MoveError::UnknownStack { step: 1, id: 3 }
+        );
+    }
 }
@@ -1,25 +1,96 @@
-use std::collections::HashSet;
+use crate::solution::impl_solution;
+use std::collections::VecDeque;
+use std::io::{self, BufReader, Read};
 
 #[aoc(day6, part1)]
 pub fn solve_part1(input: &str) -> usize {
-    first_different_n(input, 4)
+    first_different_n(input, 4).expect("no start-of-packet marker")
 }
 
 #[aoc(day6, part2)]
 pub fn solve_part2(input: &str) -> usize {
-    first_different_n(input, 14)
+    first_different_n(input, 14).expect("no start-of-message marker")
 }
 
-fn first_different_n(input: &str, n: usize) -> usize {
-    for i in 0..input.len() {
-        let window_end = i + n;
-        let window: &str = &input[i..window_end];
-        let window_set: HashSet<char> = HashSet::from_iter(window.chars());
-        if n == window_set.len() {
-            return window_end;
+fn first_different_n(input: &str, n: usize) -> Option<usize> {
+    MarkerDetector::new(n).first(input.bytes())
+}
+
+/// Finds positions where the last `n` bytes are all different.
+/// Keeps a count per byte value, so every byte is handled in constant time.
+pub struct MarkerDetector {
+    n: usize,
+    window: VecDeque<u8>,
+    counts: [usize; 256],
+    distinct: usize,
+    position: usize,
+}
+
+impl MarkerDetector {
+    pub fn new(n: usize) -> Self {
+        assert!(n > 0, "marker length must be at least 1");
+        MarkerDetector {
+            n,
+            window: VecDeque::with_capacity(n + 1),
+            counts: [0; 256],
+            distinct: 0,
+            position: 0,
+        }
+    }
+
+    /// Feeds the next byte. Returns the number of bytes consumed so far if
+    /// they end with a marker.
+    pub fn push(&mut self, byte: u8) -> Option<usize> {
+        self.position += 1;
+        self.window.push_back(byte);
+        self.counts[byte as usize] += 1;
+        if self.counts[byte as usize] == 1 {
+            self.distinct += 1;
+        }
+        if self.window.len() > self.n {
+            let old = self.window.pop_front().unwrap();
+            self.counts[old as usize] -= 1;
+            if self.counts[old as usize] == 0 {
+                self.distinct -= 1;
+            }
+        }
+        if self.distinct == self.n {
+            Some(self.position)
+        } else {
+            None
+        }
+    }
+
+    pub fn first<I: IntoIterator<Item = u8>>(&mut self, bytes: I) -> Option<usize> {
+        bytes.into_iter().find_map(|b| self.push(b))
+    }
+
+    /// Every position that ends a marker, lazily as the bytes are consumed.
+    pub fn markers<I: IntoIterator<Item = u8>>(self, bytes: I) -> impl Iterator<Item = usize> {
+        let mut detector = self;
+        bytes.into_iter().filter_map(move |b| detector.push(b))
+    }
+
+    pub fn first_in<R: Read>(&mut self, reader: R) -> io::Result<Option<usize>> {
+        for byte in BufReader::new(reader).bytes() {
+            if let Some(position) = self.push(byte?) {
+                return Ok(Some(position));
+            }
         }
+        Ok(None)
+    }
+}
+
+pub struct Day6;
+
+impl_solution! {
+    Day6 {
+        day: 6,
+        input: String,
+        parse: |input: &str| input.to_string(),
+        part1: solve_part1,
+        part2: solve_part2,
     }
-    0
 }
 
 #[cfg(test)]
@@ -37,4 +108,32 @@ mod tests {
         let result = solve_part1("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw");
         assert_eq!(result, 11);
     }
+
+    #[test]
+    fn it_should_solve_part2() {
+        assert_eq!(solve_part2("mjqjpqmgbljsphdztnvjfqwrcgsmlb"), 19);
+        assert_eq!(solve_part2("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw"), 26);
+    }
+
+    #[test]
+    fn it_should_not_find_missing_marker() {
+        assert_eq!(first_different_n("aabbaabb", 4), None);
+        assert_eq!(first_different_n("abc", 4), None);
+        assert_eq!(first_different_n("", 4), None);
+        assert_eq!(first_different_n("abcd", 4), Some(4));
+    }
+
+    #[test]
+    fn it_should_find_all_markers() {
+        let markers = MarkerDetector::new(3)
+            .markers("aabcabb".bytes())
+            .collect::<Vec<_>>();
+        assert_eq!(markers, vec![4, 5, 6]);
+    }
+
+    #[test]
+    fn it_should_read_from_reader() {
+        let reader = io::Cursor::new("bvwbjplbgvbhsrlpgdmjqwftvncz\n");
+        assert_eq!(MarkerDetector::new(4).first_in(reader).unwrap(), Some(5));
+    }
 }
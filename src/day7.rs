@@ -1,3 +1,4 @@
+use crate::solution::impl_solution;
 use camino::*;
 use id_tree::*;
 pub struct Dir {
@@ -83,6 +84,18 @@ pub fn solve_part2(tree: &Tree<Dir>) -> usize {
     total_size - needed_space
 }
 
+pub struct Day7;
+
+impl_solution! {
+    Day7 {
+        day: 7,
+        input: Tree<Dir>,
+        parse: input_generator,
+        part1: solve_part1,
+        part2: solve_part2,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,4 +1,7 @@
+use crate::solution::impl_solution;
 use std::collections::HashSet;
+use std::iter::successors;
+use std::str::FromStr;
 
 #[aoc_generator(day8)]
 pub fn input_generator(input: &str) -> Vec<Vec<u8>> {
@@ -9,44 +12,305 @@ pub fn input_generator(input: &str) -> Vec<Vec<u8>> {
 }
 
 #[aoc(day8, part1)]
-pub fn solve_part1(input: &Vec<Vec<u8>>) -> usize {
-    let mut trees_seen: HashSet<(u8, u8)> = HashSet::new();
+pub fn solve_part1(input: &[Vec<u8>]) -> usize {
+    Views::of(input).visible_count()
+}
+
+#[aoc(day8, part2)]
+pub fn solve_part2(input: &[Vec<u8>]) -> u64 {
+    Views::of(input).best_scenic_score()
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    InvalidHeight(usize, String),
+    RaggedRow(usize),
+}
+
+/// Parses a heightmap with one row per line and comma separated heights,
+/// for heights that do not fit into a single digit.
+/// Errors carry the 1-based line number, counting blank lines too.
+pub fn parse_comma_separated<T: FromStr>(input: &str) -> Result<Vec<Vec<T>>, ParseError> {
+    let rows = input
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(i, l)| {
+            l.split(',')
+                .map(|h| {
+                    h.trim()
+                        .parse::<T>()
+                        .map_err(|_| ParseError::InvalidHeight(i + 1, h.to_string()))
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map(|row| (i + 1, row))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if let Some((line, _)) = rows.iter().find(|(_, r)| r.len() != rows[0].1.len()) {
+        return Err(ParseError::RaggedRow(*line));
+    }
+    Ok(rows.into_iter().map(|(_, row)| row).collect())
+}
+
+/// Direction a tree looks in, as a step on the grid where `y` grows downwards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Direction {
+    pub dx: isize,
+    pub dy: isize,
+}
+
+impl Direction {
+    pub const N: Direction = Direction { dx: 0, dy: -1 };
+    pub const NE: Direction = Direction { dx: 1, dy: -1 };
+    pub const E: Direction = Direction { dx: 1, dy: 0 };
+    pub const SE: Direction = Direction { dx: 1, dy: 1 };
+    pub const S: Direction = Direction { dx: 0, dy: 1 };
+    pub const SW: Direction = Direction { dx: -1, dy: 1 };
+    pub const W: Direction = Direction { dx: -1, dy: 0 };
+    pub const NW: Direction = Direction { dx: -1, dy: -1 };
+}
+
+/// Which trees block the view of a tree of a given height.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Blocking {
+    /// Trees of the same height or taller, as in the puzzle.
+    AtLeastAsTall,
+    /// Only trees that are strictly taller.
+    Taller,
+}
+
+impl Blocking {
+    fn blocks<T: Ord>(&self, tree: &T, height: &T) -> bool {
+        match self {
+            Blocking::AtLeastAsTall => tree >= height,
+            Blocking::Taller => tree > height,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Sight {
+    directions: Vec<Direction>,
+    blocking: Blocking,
+}
+
+impl Sight {
+    pub fn new(directions: Vec<Direction>, blocking: Blocking) -> Self {
+        assert!(
+            directions.iter().all(|d| d.dx != 0 || d.dy != 0),
+            "a direction must move"
+        );
+        Sight {
+            directions,
+            blocking,
+        }
+    }
+
+    /// Up, down, left and right, as in the puzzle.
+    pub fn axes() -> Self {
+        Sight::new(
+            vec![Direction::N, Direction::E, Direction::S, Direction::W],
+            Blocking::AtLeastAsTall,
+        )
+    }
+
+    /// The axes and the four diagonals.
+    pub fn compass() -> Self {
+        Sight::new(
+            vec![
+                Direction::N,
+                Direction::NE,
+                Direction::E,
+                Direction::SE,
+                Direction::S,
+                Direction::SW,
+                Direction::W,
+                Direction::NW,
+            ],
+            Blocking::AtLeastAsTall,
+        )
+    }
+
+    pub fn blocking(mut self, blocking: Blocking) -> Self {
+        self.blocking = blocking;
+        self
+    }
+}
+
+/// Visibility and scenic score of every tree, computed with one monotonic
+/// stack pass per direction.
+pub struct Views {
+    width: usize,
+    visible: Vec<bool>,
+    scenic: Vec<u64>,
+}
+
+impl Views {
+    pub fn of(grid: &[Vec<u8>]) -> Self {
+        Views::with(grid, &Sight::axes())
+    }
+
+    pub fn with<T: Copy + Ord + Sync>(grid: &[Vec<T>], sight: &Sight) -> Self {
+        let height = grid.len();
+        let width = grid.first().map(|r| r.len()).unwrap_or(0);
+        assert!(
+            grid.iter().all(|r| r.len() == width),
+            "all rows must have the same length"
+        );
+        let heights = grid.concat();
+        let mut views = Views::empty(width, height);
+        #[cfg(not(feature = "parallel"))]
+        for d in &sight.directions {
+            views.look(&heights, height, d, sight.blocking);
+        }
+        #[cfg(feature = "parallel")]
+        {
+            use rayon::prelude::*;
+            // each direction gets its own views, combining them does not
+            // depend on the order so the result is the same as sequentially
+            let directions = sight
+                .directions
+                .par_iter()
+                .map(|d| {
+                    let mut direction = Views::empty(width, height);
+                    direction.look(&heights, height, d, sight.blocking);
+                    direction
+                })
+                .collect::<Vec<_>>();
+            for direction in directions {
+                views.combine(&direction);
+            }
+        }
+        views
+    }
+
+    fn empty(width: usize, height: usize) -> Self {
+        Views {
+            width,
+            visible: vec![false; width * height],
+            scenic: vec![1; width * height],
+        }
+    }
+
+    /// Scans every line of sight in direction `d`.
+    fn look<T: Copy + Ord>(
+        &mut self,
+        heights: &[T],
+        height: usize,
+        d: &Direction,
+        blocking: Blocking,
+    ) {
+        let width = self.width;
+        let in_grid =
+            |x: isize, y: isize| x >= 0 && y >= 0 && (x as usize) < width && (y as usize) < height;
+        let mut stack = Vec::new();
+        // every line of sight starts at the tree closest to the edge it looks at
+        for y in 0..height as isize {
+            for x in 0..width as isize {
+                if in_grid(x + d.dx, y + d.dy) {
+                    continue;
+                }
+                let line = successors(Some((x, y)), |(x, y)| {
+                    Some((x - d.dx, y - d.dy)).filter(|(x, y)| in_grid(*x, *y))
+                })
+                .map(|(x, y)| y as usize * width + x as usize);
+                self.scan(heights, line, blocking, &mut stack);
+            }
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    fn combine(&mut self, other: &Views) {
+        for (visible, other) in self.visible.iter_mut().zip(&other.visible) {
+            *visible |= other;
+        }
+        for (scenic, other) in self.scenic.iter_mut().zip(&other.scenic) {
+            *scenic *= other;
+        }
+    }
+
+    /// Looks back along `line` from every tree. The stack keeps the trees
+    /// that are not hidden behind a later tree blocking them, so the top of
+    /// the stack after popping the non blocking trees is the tree blocking the view.
+    fn scan<T: Copy + Ord, I: Iterator<Item = usize>>(
+        &mut self,
+        heights: &[T],
+        line: I,
+        blocking: Blocking,
+        stack: &mut Vec<(usize, T)>,
+    ) {
+        stack.clear();
+        for (i, index) in line.enumerate() {
+            let height = heights[index];
+            while stack
+                .last()
+                .is_some_and(|(_, h)| !blocking.blocks(h, &height))
+            {
+                stack.pop();
+            }
+            match stack.last() {
+                Some((blocking, _)) => self.scenic[index] *= (i - blocking) as u64,
+                None => {
+                    self.visible[index] = true;
+                    self.scenic[index] *= i as u64;
+                }
+            }
+            stack.push((i, height));
+        }
+    }
+
+    pub fn is_visible(&self, x: usize, y: usize) -> bool {
+        self.visible[y * self.width + x]
+    }
+
+    pub fn scenic_score(&self, x: usize, y: usize) -> u64 {
+        self.scenic[y * self.width + x]
+    }
+
+    pub fn visible_count(&self) -> usize {
+        self.visible.iter().filter(|v| **v).count()
+    }
+
+    pub fn best_scenic_score(&self) -> u64 {
+        self.scenic.iter().copied().max().unwrap_or(0)
+    }
+}
+
+/// The straightforward approach, kept to compare against in tests and benchmarks.
+pub fn solve_part1_naive(input: &[Vec<u8>]) -> usize {
+    let mut trees_seen: HashSet<(usize, usize)> = HashSet::new();
     for (row_number, row) in input.iter().enumerate() {
-        visible_in_sequence_left_to_right(&row)
-            .iter()
-            .for_each(|i| {
-                trees_seen.insert((*i, row_number as u8));
-            });
+        visible_in_sequence_left_to_right(row).iter().for_each(|i| {
+            trees_seen.insert((*i, row_number));
+        });
 
-        visible_in_sequence_right_to_left(&row)
-            .iter()
-            .for_each(|i| {
-                trees_seen.insert((*i, row_number as u8));
-            });
+        visible_in_sequence_right_to_left(row).iter().for_each(|i| {
+            trees_seen.insert((*i, row_number));
+        });
     }
-    for col in 0..input.first().unwrap().len() {
-        let column = col_at(col as u8, &input);
+    for col in 0..input.first().map(|r| r.len()).unwrap_or(0) {
+        let column = col_at(col, input);
         visible_in_sequence_left_to_right(&column)
             .iter()
             .for_each(|i| {
-                trees_seen.insert((col as u8, *i));
+                trees_seen.insert((col, *i));
             });
 
         visible_in_sequence_right_to_left(&column)
             .iter()
             .for_each(|i| {
-                trees_seen.insert((col as u8, *i));
+                trees_seen.insert((col, *i));
             });
     }
     trees_seen.len()
 }
 
-#[aoc(day8, part2)]
-pub fn solve_part2(input: &Vec<Vec<u8>>) -> u32 {
+/// The straightforward approach, kept to compare against in tests and benchmarks.
+pub fn solve_part2_naive(input: &[Vec<u8>]) -> u64 {
     let mut max = 0;
     for row_number in 0..input.len() {
         for col_number in 0..input[0].len() {
-            let score = scenic_score((col_number, row_number), &input);
+            let score = scenic_score((col_number, row_number), input);
             if score > max {
                 max = score
             }
@@ -55,9 +319,9 @@ pub fn solve_part2(input: &Vec<Vec<u8>>) -> u32 {
     max
 }
 
-fn scenic_score(tree: (usize, usize), matrix: &Vec<Vec<u8>>) -> u32 {
+fn scenic_score(tree: (usize, usize), matrix: &[Vec<u8>]) -> u64 {
     let height = matrix[tree.1][tree.0];
-    let col = col_at(tree.0 as u8, &matrix);
+    let col = col_at(tree.0, matrix);
     let row = matrix.get(tree.1).unwrap();
 
     let down = view_to_right(height, &col.as_slice()[tree.1 + 1..]);
@@ -68,7 +332,7 @@ fn scenic_score(tree: (usize, usize), matrix: &Vec<Vec<u8>>) -> u32 {
     left * right * up * down
 }
 
-fn view_to_right(height: u8, neighbors: &[u8]) -> u32 {
+fn view_to_right(height: u8, neighbors: &[u8]) -> u64 {
     let mut count = 0;
     for n in neighbors {
         count += 1;
@@ -80,7 +344,7 @@ fn view_to_right(height: u8, neighbors: &[u8]) -> u32 {
     count
 }
 
-fn view_to_left(height: u8, neighbors: &[u8]) -> u32 {
+fn view_to_left(height: u8, neighbors: &[u8]) -> u64 {
     let mut count = 0;
     for n in neighbors.iter().rev() {
         count += 1;
@@ -92,34 +356,43 @@ fn view_to_left(height: u8, neighbors: &[u8]) -> u32 {
     count
 }
 
-fn col_at(col: u8, trees: &Vec<Vec<u8>>) -> Vec<u8> {
-    trees
-        .iter()
-        .map(|i| *i.get(col as usize).unwrap())
-        .collect()
+fn col_at(col: usize, trees: &[Vec<u8>]) -> Vec<u8> {
+    trees.iter().map(|i| *i.get(col).unwrap()).collect()
 }
 
-pub fn visible_in_sequence_left_to_right(trees: &Vec<u8>) -> Vec<u8> {
+pub fn visible_in_sequence_left_to_right(trees: &[u8]) -> Vec<usize> {
     let mut max: i32 = -1;
-    let mut visible: Vec<u8> = Vec::new();
+    let mut visible: Vec<usize> = Vec::new();
     for (i, v) in trees.iter().enumerate() {
         if *v as i32 > max {
             max = *v as i32;
-            visible.push(i as u8);
+            visible.push(i);
         }
     }
     visible
 }
 
-pub fn visible_in_sequence_right_to_left(trees: &Vec<u8>) -> Vec<u8> {
-    let mut rev = trees.clone();
+pub fn visible_in_sequence_right_to_left(trees: &[u8]) -> Vec<usize> {
+    let mut rev = trees.to_vec();
     rev.reverse();
     visible_in_sequence_left_to_right(&rev)
         .iter()
-        .map(|i| trees.len() as u8 - i - 1)
+        .map(|i| trees.len() - i - 1)
         .collect()
 }
 
+pub struct Day8;
+
+impl_solution! {
+    Day8 {
+        day: 8,
+        input: Vec<Vec<u8>>,
+        parse: input_generator,
+        part1: solve_part1,
+        part2: solve_part2,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -175,4 +448,140 @@ mod tests {
         let result = solve_part2(&input);
         assert_eq!(result, 8);
     }
+
+    /// Pseudo random grid, so the tests and benchmarks do not need a random crate.
+    fn grid(width: usize, height: usize, seed: u64) -> Vec<Vec<u8>> {
+        let mut state = seed;
+        (0..height)
+            .map(|_| {
+                (0..width)
+                    .map(|_| {
+                        state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+                        ((state >> 33) % 10) as u8
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn should_match_naive_approach() {
+        for (width, height, seed) in [(1, 1, 1), (5, 3, 2), (17, 23, 3), (300, 4, 4), (4, 300, 5)] {
+            let input = grid(width, height, seed);
+            assert_eq!(solve_part1(&input), solve_part1_naive(&input));
+            assert_eq!(solve_part2(&input), solve_part2_naive(&input));
+        }
+    }
+
+    #[test]
+    fn should_compute_views_per_tree() {
+        let views = Views::of(&input_generator("30373\n25512\n65332\n33549\n35390"));
+        assert!(views.is_visible(1, 1));
+        assert!(!views.is_visible(2, 2));
+        assert_eq!(views.scenic_score(2, 1), 4);
+        assert_eq!(views.scenic_score(2, 3), 8);
+        assert_eq!(views.scenic_score(0, 0), 0);
+    }
+
+    #[test]
+    fn should_handle_wide_grids() {
+        let input = vec![vec![0u8; 400]; 3];
+        assert_eq!(solve_part1(&input), 2 * 400 + 2);
+        assert_eq!(solve_part2(&input), 1);
+    }
+
+    /// Walks every line of sight tree by tree.
+    fn brute_force<T: Copy + Ord>(grid: &[Vec<T>], sight: &Sight) -> (usize, u64) {
+        let (w, h) = (grid[0].len() as isize, grid.len() as isize);
+        let mut visible = 0;
+        let mut best = 0;
+        for y in 0..h {
+            for x in 0..w {
+                let tree = grid[y as usize][x as usize];
+                let mut seen = false;
+                let mut score = 1;
+                for d in &sight.directions {
+                    let (mut cx, mut cy, mut distance) = (x + d.dx, y + d.dy, 0);
+                    let mut blocked = false;
+                    while cx >= 0 && cy >= 0 && cx < w && cy < h {
+                        distance += 1;
+                        if sight
+                            .blocking
+                            .blocks(&grid[cy as usize][cx as usize], &tree)
+                        {
+                            blocked = true;
+                            break;
+                        }
+                        cx += d.dx;
+                        cy += d.dy;
+                    }
+                    seen |= !blocked;
+                    score *= distance;
+                }
+                visible += seen as usize;
+                best = best.max(score);
+            }
+        }
+        (visible, best)
+    }
+
+    #[test]
+    fn should_match_brute_force_in_any_direction() {
+        let sights = [
+            Sight::axes(),
+            Sight::compass(),
+            Sight::compass().blocking(Blocking::Taller),
+            Sight::new(
+                vec![Direction { dx: 1, dy: 2 }, Direction::W],
+                Blocking::AtLeastAsTall,
+            ),
+        ];
+        for (width, height, seed) in [(1, 1, 1), (5, 3, 2), (17, 23, 3), (40, 7, 4)] {
+            let input = grid(width, height, seed);
+            for sight in &sights {
+                let views = Views::with(&input, sight);
+                assert_eq!(
+                    (views.visible_count(), views.best_scenic_score()),
+                    brute_force(&input, sight),
+                    "{:?}",
+                    sight
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn should_see_over_trees_of_same_height() {
+        let input = vec![vec![1u32, 1, 1, 1], vec![1, 1, 1, 1], vec![1, 1, 1, 1]];
+        let views = Views::with(&input, &Sight::axes().blocking(Blocking::Taller));
+        assert_eq!(views.visible_count(), 12);
+        assert_eq!(views.scenic_score(1, 1), 2);
+
+        let views = Views::with(&input, &Sight::compass());
+        assert_eq!(views.visible_count(), 10);
+    }
+
+    #[test]
+    fn should_parse_comma_separated_heights() {
+        let input = parse_comma_separated::<u16>("300,12,7\n1, 2,65535").unwrap();
+        assert_eq!(input, vec![vec![300, 12, 7], vec![1, 2, 65535]]);
+        assert_eq!(Views::with(&input, &Sight::axes()).visible_count(), 6);
+
+        assert_eq!(
+            parse_comma_separated::<u16>("1,2\n1,x"),
+            Err(ParseError::InvalidHeight(2, "x".to_string()))
+        );
+        assert_eq!(
+            parse_comma_separated::<u16>("1,2\n1"),
+            Err(ParseError::RaggedRow(2))
+        );
+        assert_eq!(
+            parse_comma_separated::<u16>("1,2\n\n1,2,3"),
+            Err(ParseError::RaggedRow(3))
+        );
+        assert_eq!(
+            parse_comma_separated::<u16>("1,2\n\n1,x"),
+            Err(ParseError::InvalidHeight(3, "x".to_string()))
+        );
+    }
 }
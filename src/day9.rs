@@ -1,3 +1,4 @@
+use crate::solution::impl_solution;
 use std::{collections::HashSet, fmt};
 
 #[derive(Debug, Clone, Copy)]
@@ -184,6 +185,18 @@ fn move_head(pos: &Pos, m: &Move) -> Pos {
     }
 }
 
+pub struct Day9;
+
+impl_solution! {
+    Day9 {
+        day: 9,
+        input: Vec<Move>,
+        parse: input_generator,
+        part1: solve_part1,
+        part2: solve_part2,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -206,31 +219,14 @@ mod tests {
 
     #[test]
     fn it_should_solve_part1_example() {
-        let input = input_generator(
-            "R 4
-                U 4
-                L 3
-                D 1
-                R 4
-                D 1
-                L 5\nR 2",
-        );
+        let input = input_generator(include_str!("../examples/day9/example1.txt"));
         let result = solve_part1(&input);
 
         assert_eq!(result, 13);
     }
     #[test]
     fn it_should_solve_part2_example() {
-        let input = input_generator(
-            "R 5
-                U 8
-                L 8
-                D 3
-                R 17
-                D 10
-                L 25
-                U 20",
-        );
+        let input = input_generator(include_str!("../examples/day9/example2.txt"));
         let result = solve_part2(&input);
 
         assert_eq!(result, 36);
@@ -0,0 +1,273 @@
+//! Inputs live in a store directory, `input/2022` by default. The input the
+//! answers are recorded for is `dayN.txt`, other named inputs go in
+//! `dayN/<name>.txt`. The sha256 of every input is kept in `checksums.toml`,
+//! and a [`Fetch`] copies missing inputs from elsewhere.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crypto::digest::Digest;
+use crypto::sha2::Sha256;
+use serde::{Deserialize, Serialize};
+
+/// Name of the input stored as `dayN.txt`, the one the answers are recorded
+/// for. Other inputs are stored as `dayN/<name>.txt`.
+pub const DEFAULT_INPUT: &str = "default";
+
+const CHECKSUMS: &str = "checksums.toml";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum InputError {
+    Io(String),
+    InvalidToml(String),
+    /// Day and name of an input that is neither stored nor fetchable.
+    Missing(u32, String),
+    /// Day and name of an input that changed since its checksum was recorded.
+    ChecksumMismatch(u32, String),
+}
+
+/// Gets inputs that are not in the store yet.
+pub trait Fetch {
+    fn fetch(&self, day: u32, name: &str) -> Result<String, InputError>;
+}
+
+/// Fetches from another directory laid out like the store, such as a shared
+/// folder of inputs.
+pub struct LocalFiles {
+    pub dir: PathBuf,
+}
+
+impl Fetch for LocalFiles {
+    fn fetch(&self, day: u32, name: &str) -> Result<String, InputError> {
+        read(&input_path(&self.dir, day, name), day, name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checksum {
+    pub day: u32,
+    pub name: String,
+    pub sha256: String,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct Checksums {
+    #[serde(default, rename = "input")]
+    inputs: Vec<Checksum>,
+}
+
+/// Puzzle inputs in a directory, checked against the sha256 recorded for
+/// them in its `checksums.toml`.
+pub struct InputStore {
+    dir: PathBuf,
+    checksums: Checksums,
+    fetch: Option<Box<dyn Fetch>>,
+}
+
+pub fn checksum(input: &str) -> String {
+    let mut sha = Sha256::new();
+    sha.input_str(input);
+    sha.result_str()
+}
+
+fn input_path(dir: &Path, day: u32, name: &str) -> PathBuf {
+    if name == DEFAULT_INPUT {
+        dir.join(format!("day{}.txt", day))
+    } else {
+        dir.join(format!("day{}", day))
+            .join(format!("{}.txt", name))
+    }
+}
+
+fn read(path: &Path, day: u32, name: &str) -> Result<String, InputError> {
+    fs::read_to_string(path).map_err(|e| match e.kind() {
+        io::ErrorKind::NotFound => InputError::Missing(day, name.to_string()),
+        _ => InputError::Io(format!("{}: {}", path.display(), e)),
+    })
+}
+
+fn io_error(path: &Path, e: io::Error) -> InputError {
+    InputError::Io(format!("{}: {}", path.display(), e))
+}
+
+impl InputStore {
+    /// Opens the store, a directory without checksums has none recorded yet.
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self, InputError> {
+        let dir = dir.into();
+        let path = dir.join(CHECKSUMS);
+        let checksums = match fs::read_to_string(&path) {
+            Ok(toml) => {
+                toml::from_str(&toml).map_err(|e| InputError::InvalidToml(e.to_string()))?
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Checksums::default(),
+            Err(e) => return Err(io_error(&path, e)),
+        };
+        Ok(InputStore {
+            dir,
+            checksums,
+            fetch: None,
+        })
+    }
+
+    /// Missing inputs are fetched and stored when loading them.
+    pub fn with_fetch(mut self, fetch: Box<dyn Fetch>) -> Self {
+        self.fetch = Some(fetch);
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn path(&self, day: u32, name: &str) -> PathBuf {
+        input_path(&self.dir, day, name)
+    }
+
+    /// Names of the inputs stored for a day, the default one first.
+    pub fn names(&self, day: u32) -> Result<Vec<String>, InputError> {
+        let mut names = Vec::new();
+        if self.path(day, DEFAULT_INPUT).is_file() {
+            names.push(DEFAULT_INPUT.to_string());
+        }
+        let dir = self.dir.join(format!("day{}", day));
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(names),
+            Err(e) => return Err(io_error(&dir, e)),
+        };
+        let mut named = entries
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.extension().is_some_and(|e| e == "txt"))
+            .filter_map(|p| Some(p.file_stem()?.to_string_lossy().to_string()))
+            .filter(|name| name != DEFAULT_INPUT)
+            .collect::<Vec<_>>();
+        named.sort();
+        names.extend(named);
+        Ok(names)
+    }
+
+    pub fn checksum(&self, day: u32, name: &str) -> Option<&str> {
+        self.checksums
+            .inputs
+            .iter()
+            .find(|c| c.day == day && c.name == name)
+            .map(|c| c.sha256.as_str())
+    }
+
+    /// Reads an input, fetching it first if it is not stored. Inputs with a
+    /// recorded checksum must still match it.
+    pub fn load(&mut self, day: u32, name: &str) -> Result<String, InputError> {
+        let input = match read(&self.path(day, name), day, name) {
+            Err(InputError::Missing(..)) if self.fetch.is_some() => {
+                let input = self.fetch.as_ref().unwrap().fetch(day, name)?;
+                self.store(day, name, &input)?;
+                input
+            }
+            input => input?,
+        };
+        match self.checksum(day, name) {
+            Some(sha256) if sha256 != checksum(&input) => {
+                Err(InputError::ChecksumMismatch(day, name.to_string()))
+            }
+            _ => Ok(input),
+        }
+    }
+
+    /// Writes an input and records its checksum.
+    pub fn store(&mut self, day: u32, name: &str, input: &str) -> Result<(), InputError> {
+        let path = self.path(day, name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| io_error(parent, e))?;
+        }
+        fs::write(&path, input).map_err(|e| io_error(&path, e))?;
+        self.record(day, name, &checksum(input))
+    }
+
+    /// Records the checksum of an input, replacing an earlier one, and saves
+    /// them sorted by day and name.
+    pub fn record(&mut self, day: u32, name: &str, sha256: &str) -> Result<(), InputError> {
+        let entry = Checksum {
+            day,
+            name: name.to_string(),
+            sha256: sha256.to_string(),
+        };
+        let inputs = &mut self.checksums.inputs;
+        match inputs.binary_search_by(|c| (c.day, c.name.as_str()).cmp(&(day, name))) {
+            Ok(i) => inputs[i] = entry,
+            Err(i) => inputs.insert(i, entry),
+        }
+        let path = self.dir.join(CHECKSUMS);
+        fs::write(&path, toml::to_string(&self.checksums).unwrap()).map_err(|e| io_error(&path, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn empty_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("adventofcode22-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn it_should_list_named_inputs() {
+        let dir = empty_dir("names");
+        let mut store = InputStore::open(&dir).unwrap();
+        store.store(1, "bob", "2").unwrap();
+        store.store(1, DEFAULT_INPUT, "1").unwrap();
+        store.store(1, "alice", "3").unwrap();
+
+        assert_eq!(
+            store.names(1),
+            Ok(vec!["default".into(), "alice".into(), "bob".into()])
+        );
+        assert_eq!(store.names(2), Ok(vec![]));
+        assert!(dir.join("day1.txt").is_file());
+        assert_eq!(
+            InputStore::open(&dir).unwrap().load(1, "alice"),
+            Ok("3".to_string())
+        );
+        assert_eq!(
+            store.load(1, "carol"),
+            Err(InputError::Missing(1, "carol".into()))
+        );
+    }
+
+    #[test]
+    fn it_should_reject_changed_inputs() {
+        let dir = empty_dir("checksums");
+        let mut store = InputStore::open(&dir).unwrap();
+        store
+            .store(6, "example1", "mjqjpqmgbljsphdztnvjfqwrcgsmlb")
+            .unwrap();
+        fs::write(store.path(6, "example1"), "bvwbjplbgvbhsrlpgdmjqwftvncz").unwrap();
+
+        assert_eq!(
+            InputStore::open(&dir).unwrap().load(6, "example1"),
+            Err(InputError::ChecksumMismatch(6, "example1".into()))
+        );
+    }
+
+    #[test]
+    fn it_should_fetch_missing_inputs() {
+        let shared = empty_dir("shared");
+        fs::create_dir_all(shared.join("day2")).unwrap();
+        fs::write(shared.join("day2/alice.txt"), "A Y").unwrap();
+        let dir = empty_dir("fetched");
+        let mut store = InputStore::open(&dir)
+            .unwrap()
+            .with_fetch(Box::new(LocalFiles { dir: shared }));
+
+        assert_eq!(store.load(2, "alice"), Ok("A Y".to_string()));
+        assert_eq!(store.checksum(2, "alice"), Some(checksum("A Y").as_str()));
+        assert_eq!(
+            InputStore::open(&dir).unwrap().names(2),
+            Ok(vec!["alice".into()])
+        );
+    }
+}
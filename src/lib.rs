@@ -4,6 +4,9 @@ extern crate aoc_runner;
 extern crate aoc_runner_derive;
 extern crate crypto;
 
+pub mod answer;
+pub mod answers;
+pub mod context;
 pub mod day1;
 pub mod day2;
 pub mod day3;
@@ -19,5 +22,15 @@ pub mod day12;
 pub mod day13;
 pub mod day14;
 pub mod day15;
+pub mod inputs;
+pub mod normalize;
+pub mod registry;
+pub mod solution;
+
+pub use answer::Answer;
+pub use context::SolveContext;
+pub use normalize::Normalization;
+pub use registry::{solve, solve_with};
+pub use solution::Solution;
 
 aoc_lib! { year = 2022 }
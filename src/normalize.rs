@@ -0,0 +1,135 @@
+//! Inputs saved on Windows and pasted examples parse like the originals: a
+//! byte order mark is dropped, `\r\n` becomes `\n`, trailing newlines are
+//! trimmed and common indentation is removed. Day 5 keeps its indentation
+//! since its crates are found by column.
+
+use std::borrow::Cow;
+
+/// Cleanups applied to the raw input before a day parses it, so inputs saved
+/// on Windows or examples pasted with indentation parse like the originals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Normalization {
+    pub strip_bom: bool,
+    /// Turns `\r\n` line endings into `\n`.
+    pub crlf: bool,
+    pub trim_trailing_newlines: bool,
+    /// Removes the indentation all non blank lines have in common.
+    pub dedent: bool,
+}
+
+impl Default for Normalization {
+    fn default() -> Self {
+        Normalization {
+            strip_bom: true,
+            crlf: true,
+            trim_trailing_newlines: true,
+            dedent: true,
+        }
+    }
+}
+
+impl Normalization {
+    /// Leaves the input as it is.
+    pub fn none() -> Self {
+        Normalization {
+            strip_bom: false,
+            crlf: false,
+            trim_trailing_newlines: false,
+            dedent: false,
+        }
+    }
+
+    pub fn with_dedent(mut self, dedent: bool) -> Self {
+        self.dedent = dedent;
+        self
+    }
+
+    /// Only allocates when something changes.
+    pub fn apply<'a>(&self, input: &'a str) -> Cow<'a, str> {
+        let mut input = Cow::Borrowed(input);
+        if self.strip_bom {
+            if let Cow::Borrowed(s) = input {
+                input = Cow::Borrowed(s.strip_prefix('\u{feff}').unwrap_or(s));
+            }
+        }
+        if self.crlf && input.contains("\r\n") {
+            input = Cow::Owned(input.replace("\r\n", "\n"));
+        }
+        if self.dedent {
+            if let Some(dedented) = dedent(&input) {
+                input = Cow::Owned(dedented);
+            }
+        }
+        if self.trim_trailing_newlines {
+            input = match input {
+                Cow::Borrowed(s) => Cow::Borrowed(s.trim_end_matches(['\n', '\r'])),
+                Cow::Owned(mut s) => {
+                    s.truncate(s.trim_end_matches(['\n', '\r']).len());
+                    Cow::Owned(s)
+                }
+            };
+        }
+        input
+    }
+}
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start_matches([' ', '\t']).len()
+}
+
+/// `None` if no line is indented.
+fn dedent(input: &str) -> Option<String> {
+    let indent = input
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(indentation)
+        .min()
+        .filter(|indent| *indent > 0)?;
+    let lines = input
+        .split('\n')
+        .map(|l| {
+            if indentation(l) >= indent {
+                &l[indent..]
+            } else {
+                l.trim_start()
+            }
+        })
+        .collect::<Vec<_>>();
+    Some(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_clean_up_windows_input() {
+        let input = "\u{feff}1000\r\n2000\r\n\r\n3000\r\n\r\n";
+
+        assert_eq!(Normalization::default().apply(input), "1000\n2000\n\n3000");
+        assert_eq!(Normalization::none().apply(input), input);
+    }
+
+    #[test]
+    fn it_should_dedent_pasted_examples() {
+        let input = "    [D]\n        [N] [C]\n\n        [Z] [M] [P]\n";
+
+        let result = Normalization::default().apply(input);
+
+        assert_eq!(result, "[D]\n    [N] [C]\n\n    [Z] [M] [P]");
+        assert_eq!(
+            Normalization::default().with_dedent(false).apply(input),
+            input.trim_end()
+        );
+    }
+
+    #[test]
+    fn it_should_not_copy_clean_input() {
+        let input = "A Y\nB X\nC Z";
+
+        assert!(matches!(
+            Normalization::default().apply(input),
+            Cow::Borrowed(_)
+        ));
+    }
+}
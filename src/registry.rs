@@ -0,0 +1,123 @@
+use crate::answer::Answer;
+use crate::context::{Interrupted, SolveContext};
+use crate::solution::DynSolution;
+use crate::*;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SolveError {
+    UnknownDay(u32),
+    UnknownPart(u32, u32),
+    Interrupted(Interrupted),
+}
+
+static SOLUTIONS: &[&dyn DynSolution] = &[
+    &day1::Day1,
+    &day2::Day2,
+    &day3::Day3,
+    &day4::Day4,
+    &day5::Day5,
+    &day6::Day6,
+    &day7::Day7,
+    &day8::Day8,
+    &day9::Day9,
+    &day10::Day10,
+    &day11::Day11,
+    &day12::Day12,
+    &day13::Day13,
+    &day14::Day14,
+    &day15::Day15,
+];
+
+/// Every solved day, in order.
+pub fn solutions() -> &'static [&'static dyn DynSolution] {
+    SOLUTIONS
+}
+
+pub fn solution(day: u32) -> Option<&'static dyn DynSolution> {
+    SOLUTIONS.iter().find(|s| s.day() == day).copied()
+}
+
+/// Parses the input of a day and solves one of its parts. The input is
+/// normalised the way the day asks for first.
+pub fn solve(day: u32, part: u32, input: &str) -> Result<Answer, SolveError> {
+    solution(day)
+        .ok_or(SolveError::UnknownDay(day))?
+        .solve(part, input)
+        .ok_or(SolveError::UnknownPart(day, part))
+}
+
+/// Like [`solve`], but the solver can report progress and be stopped through
+/// the context.
+pub fn solve_with(
+    day: u32,
+    part: u32,
+    input: &str,
+    context: &SolveContext,
+) -> Result<Answer, SolveError> {
+    solution(day)
+        .ok_or(SolveError::UnknownDay(day))?
+        .solve_with(part, input, context)
+        .ok_or(SolveError::UnknownPart(day, part))?
+        .map_err(SolveError::Interrupted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_should_list_days_in_order() {
+        let days = solutions().iter().map(|s| s.day()).collect::<Vec<_>>();
+        assert_eq!(days, (1..=15).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn it_should_solve_through_the_registry() {
+        assert_eq!(
+            solve(6, 1, "bvwbjplbgvbhsrlpgdmjqwftvncz"),
+            Ok(Answer::Int(5))
+        );
+        assert_eq!(solve(2, 2, "A Y\nB X\nC Z"), Ok(Answer::Int(12)));
+        assert_eq!(
+            solve(13, 2, "[1]\n[2]"),
+            Err(SolveError::UnknownPart(13, 2))
+        );
+        assert_eq!(solve(1, 3, "1"), Err(SolveError::UnknownPart(1, 3)));
+        assert_eq!(solve(25, 1, ""), Err(SolveError::UnknownDay(25)));
+        assert_eq!(solution(13).unwrap().parts(), &[1]);
+    }
+
+    #[test]
+    fn it_should_normalise_inputs_before_solving() {
+        assert_eq!(
+            solve(2, 2, "\u{feff}A Y\r\nB X\r\nC Z\r\n"),
+            Ok(Answer::Int(12))
+        );
+        assert_eq!(
+            solve(
+                14,
+                1,
+                "\n    498,4 -> 498,6 -> 496,6\n    503,4 -> 502,4 -> 502,9 -> 494,9\n"
+            ),
+            Ok(Answer::Int(24))
+        );
+        assert_eq!(
+            solve(5, 1, "    [D]    \r\n[N] [C]    \r\n[Z] [M] [P]\r\n 1   2   3 \r\n\r\nmove 1 from 2 to 1\r\nmove 3 from 1 to 3\r\nmove 2 from 2 to 1\r\nmove 1 from 1 to 2\r\n"),
+            Ok(Answer::from("CMZ"))
+        );
+    }
+
+    #[test]
+    fn it_should_stop_interrupted_solvers() {
+        let context = SolveContext::new();
+        context.cancellation().cancel();
+        assert_eq!(
+            solve_with(1, 1, "1", &context),
+            Err(SolveError::Interrupted(Interrupted::Cancelled))
+        );
+        assert_eq!(
+            solve_with(1, 1, "1", &SolveContext::new()),
+            Ok(Answer::Int(1))
+        );
+    }
+}
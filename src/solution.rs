@@ -0,0 +1,177 @@
+//! Every day implements [`Solution`] through `impl_solution!` and is listed
+//! in the registry, so `adventofcode22::solve(day, part, input)` works without
+//! aoc-runner. A day whose second part is not solved leaves out `part2`.
+
+use crate::answer::Answer;
+use crate::context::{Interrupted, SolveContext};
+use crate::normalize::Normalization;
+
+/// One day of the puzzle: how to parse its input and how to solve each part.
+pub trait Solution {
+    type Input;
+
+    fn day(&self) -> u32;
+    fn parse(&self, input: &str) -> Self::Input;
+    fn part1(&self, input: &Self::Input) -> Answer;
+
+    /// `None` while the second part is not solved.
+    fn part2(&self, _input: &Self::Input) -> Option<Answer> {
+        None
+    }
+
+    /// How the raw input is cleaned up before [`Solution::parse`] sees it.
+    fn normalization(&self) -> Normalization {
+        Normalization::default()
+    }
+
+    /// The parts that are solved, overridden by days without a second part.
+    fn parts(&self) -> &'static [u32] {
+        &[1, 2]
+    }
+
+    /// Solves part 1 under a context. Only slow solvers override this to
+    /// report progress and stop early, the others just check once.
+    fn part1_with(
+        &self,
+        input: &Self::Input,
+        context: &SolveContext,
+    ) -> Result<Answer, Interrupted> {
+        context.check()?;
+        Ok(self.part1(input))
+    }
+
+    fn part2_with(
+        &self,
+        input: &Self::Input,
+        context: &SolveContext,
+    ) -> Option<Result<Answer, Interrupted>> {
+        match context.check() {
+            Ok(()) => self.part2(input).map(Ok),
+            Err(interrupted) => Some(Err(interrupted)),
+        }
+    }
+}
+
+/// Object safe view of a [`Solution`], so that days with different input and
+/// answer types can be listed together.
+pub trait DynSolution: Sync {
+    fn day(&self) -> u32;
+    fn parts(&self) -> &'static [u32];
+    /// Parses the input and solves the part, `None` if the part is not solved.
+    fn solve(&self, part: u32, input: &str) -> Option<Answer> {
+        self.solve_with(part, input, &SolveContext::new())
+            .map(|answer| answer.expect("solving without a budget is never interrupted"))
+    }
+    fn solve_with(
+        &self,
+        part: u32,
+        input: &str,
+        context: &SolveContext,
+    ) -> Option<Result<Answer, Interrupted>>;
+}
+
+impl<S: Solution + Sync> DynSolution for S {
+    fn day(&self) -> u32 {
+        Solution::day(self)
+    }
+
+    fn parts(&self) -> &'static [u32] {
+        Solution::parts(self)
+    }
+
+    fn solve_with(
+        &self,
+        part: u32,
+        input: &str,
+        context: &SolveContext,
+    ) -> Option<Result<Answer, Interrupted>> {
+        if !Solution::parts(self).contains(&part) {
+            return None;
+        }
+        let _span = tracing::info_span!("solve", day = Solution::day(self), part).entered();
+        let input = self.parse(&self.normalization().apply(input));
+        match part {
+            1 => Some(self.part1_with(&input, context)),
+            _ => self.part2_with(&input, context),
+        }
+    }
+}
+
+/// Implements [`Solution`] for a day from its generator and solvers, which
+/// return anything an [`Answer`] can be made from. Days without a solved
+/// second part leave out `part2`.
+///
+/// ```ignore
+/// impl_solution! {
+///     Day1 {
+///         day: 1,
+///         input: Vec<Elf>,
+///         parse: input_generator,
+///         part1: solve_part1,
+///         part2: solve_part2,
+///     }
+/// }
+/// ```
+macro_rules! impl_solution {
+    (
+        $name:ident {
+            day: $day:expr,
+            input: $input:ty,
+            parse: $parse:expr,
+            part1: $part1:expr,
+            $(part2: $part2:expr,)?
+            $(part2_with: $part2_with:expr,)?
+            $(normalization: $normalization:expr,)?
+        }
+    ) => {
+        impl $crate::solution::Solution for $name {
+            type Input = $input;
+
+            fn day(&self) -> u32 {
+                $day
+            }
+
+            fn parse(&self, input: &str) -> Self::Input {
+                ($parse)(input)
+            }
+
+            fn part1(&self, input: &Self::Input) -> $crate::answer::Answer {
+                ($part1)(input).into()
+            }
+
+            $(
+                fn part2(&self, input: &Self::Input) -> Option<$crate::answer::Answer> {
+                    Some(($part2)(input).into())
+                }
+            )?
+
+            $(
+                fn part2_with(
+                    &self,
+                    input: &Self::Input,
+                    context: &$crate::context::SolveContext,
+                ) -> Option<Result<$crate::answer::Answer, $crate::context::Interrupted>> {
+                    Some(($part2_with)(input, context).map(Into::into))
+                }
+            )?
+
+            $(
+                fn normalization(&self) -> $crate::normalize::Normalization {
+                    $normalization
+                }
+            )?
+
+            fn parts(&self) -> &'static [u32] {
+                impl_solution!(@parts $($part2)?)
+            }
+        }
+    };
+    (@parts) => {
+        &[1]
+    };
+    (@parts $part2:expr) => {
+        &[1, 2]
+    };
+}
+
+pub(crate) use impl_solution;
@@ -0,0 +1,36 @@
+//! One test per answer in `examples/dayN/exampleK.answers`, generated by
+//! `build.rs`. Adding a fixture and its answers adds the tests, the answers of
+//! a fixture with a `skip: reason` line are ignored with that reason.
+
+macro_rules! example_test {
+    ($name:ident, $day:expr, $part:expr, $path:expr, $expected:expr) => {
+        #[test]
+        fn $name() {
+            let input = include_str!($path);
+            let answer = adventofcode22::solve($day, $part, input);
+            assert_eq!(answer.map(|a| a.to_string()), Ok($expected.to_string()));
+        }
+    };
+    ($name:ident, $day:expr, $part:expr, $path:expr, $expected:expr, ignore = $reason:literal) => {
+        #[test]
+        #[ignore = $reason]
+        fn $name() {
+            let input = include_str!($path);
+            let answer = adventofcode22::solve($day, $part, input);
+            assert_eq!(answer.map(|a| a.to_string()), Ok($expected.to_string()));
+        }
+    };
+}
+
+/// A fixture checks nothing without answers, so it fails until it gets some
+/// or a `skip: reason` line.
+macro_rules! example_without_answers {
+    ($name:ident, $path:expr) => {
+        #[test]
+        fn $name() {
+            panic!("{} has no answers", $path);
+        }
+    };
+}
+
+include!(concat!(env!("OUT_DIR"), "/examples.rs"));